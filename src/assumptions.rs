@@ -0,0 +1,339 @@
+// Statistical tests of the assumptions behind a least squares fit, run on
+// its residuals.
+
+use std::f64::consts::PI;
+
+use crate::distributions::{chi_squared_p_value, normal_cdf, normal_quantile};
+use crate::linalg;
+use crate::LinearFit;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+/// Result of a test asked for outside the range it is defined on.
+const UNDEFINED: TestResult = TestResult {
+    statistic: f64::NAN,
+    p_value: f64::NAN,
+};
+
+/// R² of regressing `target` on `design`, which includes the intercept.
+fn auxiliary_r_squared(design: &[Vec<f64>], target: &[f64]) -> f64 {
+    let mean = target.iter().sum::<f64>() / target.len() as f64;
+
+    let parameters =
+        linalg::least_squares(design, target).unwrap_or_else(|| vec![f64::NAN; design[0].len()]);
+    let fitted = linalg::multiply(design, &parameters);
+
+    let total: f64 = target.iter().map(|t| (t - mean) * (t - mean)).sum();
+    let residual: f64 = target
+        .iter()
+        .zip(&fitted)
+        .map(|(t, f)| (t - f) * (t - f))
+        .sum();
+
+    1.0 - residual / total
+}
+
+/// Studentized (Koenker) Breusch-Pagan test, `n · R²` of the squared
+/// residuals regressed on `design`.
+fn lagrange_multiplier(design: &[Vec<f64>], residuals: &[f64]) -> TestResult {
+    let squared: Vec<f64> = residuals.iter().map(|e| e * e).collect();
+    let statistic = residuals.len() as f64 * auxiliary_r_squared(design, &squared);
+
+    TestResult {
+        statistic,
+        p_value: chi_squared_p_value(statistic, (design[0].len() - 1) as f64),
+    }
+}
+
+impl LinearFit {
+    /// Breusch-Pagan test for heteroskedasticity linear in the features.
+    pub fn breusch_pagan(&self) -> TestResult {
+        lagrange_multiplier(&self.design, &self.residuals)
+    }
+
+    /// White's test for heteroskedasticity, using the features, their squares
+    /// and cross products.
+    pub fn white(&self) -> TestResult {
+        let design: Vec<Vec<f64>> = self
+            .design
+            .iter()
+            .map(|row| {
+                let features = &row[1..];
+                let mut expanded = row.clone();
+                for i in 0..features.len() {
+                    for j in i..features.len() {
+                        expanded.push(features[i] * features[j]);
+                    }
+                }
+                expanded
+            })
+            .collect();
+
+        lagrange_multiplier(&design, &self.residuals)
+    }
+
+    pub fn durbin_watson(&self) -> TestResult {
+        durbin_watson(&self.residuals)
+    }
+
+    pub fn ljung_box(&self, lags: usize) -> TestResult {
+        ljung_box(&self.residuals, lags)
+    }
+
+    pub fn jarque_bera(&self) -> TestResult {
+        jarque_bera(&self.residuals)
+    }
+
+    pub fn shapiro_wilk(&self) -> TestResult {
+        shapiro_wilk(&self.residuals)
+    }
+}
+
+/// Durbin-Watson statistic for first order autocorrelation. The two-sided
+/// p-value uses the large sample approximation `DW ~ N(2, 4 / n)`.
+pub fn durbin_watson(residuals: &[f64]) -> TestResult {
+    let differences: f64 = residuals
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]) * (pair[1] - pair[0]))
+        .sum();
+    let statistic = differences / linalg::dot(residuals, residuals);
+
+    let z = (statistic - 2.0) / (4.0 / residuals.len() as f64).sqrt();
+
+    TestResult {
+        statistic,
+        p_value: 2.0 * normal_cdf(-z.abs()),
+    }
+}
+
+/// Ljung-Box test that the first `lags` autocorrelations are all zero. NaN
+/// unless there are more residuals than lags.
+pub fn ljung_box(residuals: &[f64], lags: usize) -> TestResult {
+    if lags >= residuals.len() {
+        return UNDEFINED;
+    }
+
+    let length = residuals.len() as f64;
+    let mean = residuals.iter().sum::<f64>() / length;
+    let centered: Vec<f64> = residuals.iter().map(|e| e - mean).collect();
+    let variance = linalg::dot(&centered, &centered);
+
+    let statistic = length
+        * (length + 2.0)
+        * (1..=lags)
+            .map(|k| {
+                let rho = linalg::dot(&centered[k..], &centered[..centered.len() - k]) / variance;
+                rho * rho / (length - k as f64)
+            })
+            .sum::<f64>();
+
+    TestResult {
+        statistic,
+        p_value: chi_squared_p_value(statistic, lags as f64),
+    }
+}
+
+/// Jarque-Bera normality test from the sample skewness and kurtosis.
+pub fn jarque_bera(residuals: &[f64]) -> TestResult {
+    let length = residuals.len() as f64;
+    let mean = residuals.iter().sum::<f64>() / length;
+    let moment = |power: i32| -> f64 {
+        residuals
+            .iter()
+            .map(|e| (e - mean).powi(power))
+            .sum::<f64>()
+            / length
+    };
+
+    let variance = moment(2);
+    let skewness = moment(3) / variance.powf(1.5);
+    let kurtosis = moment(4) / (variance * variance);
+
+    let statistic = length / 6.0 * (skewness * skewness + (kurtosis - 3.0).powi(2) / 4.0);
+
+    TestResult {
+        statistic,
+        p_value: chi_squared_p_value(statistic, 2.0),
+    }
+}
+
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+/// Shapiro-Wilk normality test for 3 to 5000 values, following Royston's
+/// algorithm AS R94. NaN for any other number of values.
+pub fn shapiro_wilk(values: &[f64]) -> TestResult {
+    const C1: [f64; 6] = [0.0, 0.221157, -0.147981, -2.07119, 4.434685, -2.706056];
+    const C2: [f64; 6] = [0.0, 0.042981, -0.293762, -1.752461, 5.682633, -3.582633];
+    const C3: [f64; 4] = [0.544, -0.39978, 0.025054, -6.714e-4];
+    const C4: [f64; 4] = [1.3822, -0.77857, 0.062767, -0.0020322];
+    const C5: [f64; 4] = [-1.5861, -0.31082, -0.083751, 0.0038915];
+    const C6: [f64; 3] = [-0.4803, -0.082676, 0.0030302];
+    const G: [f64; 2] = [-2.273, 0.459];
+
+    if !(3..=5000).contains(&values.len()) {
+        return UNDEFINED;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let n = sorted.len();
+    let length = n as f64;
+    let half = n / 2;
+
+    // Coefficients for the lower half, the upper half mirrors them
+    let mut a = vec![0.0; half];
+    if n == 3 {
+        a[0] = 0.5_f64.sqrt();
+    } else {
+        let m: Vec<f64> = (1..=half)
+            .map(|i| normal_quantile((i as f64 - 0.375) / (length + 0.25)))
+            .collect();
+        let sum_squares = 2.0 * linalg::dot(&m, &m);
+        let root = sum_squares.sqrt();
+        let u = 1.0 / length.sqrt();
+
+        a[0] = polynomial(&C1, u) - m[0] / root;
+
+        let (first, factor) = if n > 5 {
+            a[1] = polynomial(&C2, u) - m[1] / root;
+            let factor = ((sum_squares - 2.0 * m[0] * m[0] - 2.0 * m[1] * m[1])
+                / (1.0 - 2.0 * a[0] * a[0] - 2.0 * a[1] * a[1]))
+                .sqrt();
+            (2, factor)
+        } else {
+            let factor = ((sum_squares - 2.0 * m[0] * m[0]) / (1.0 - 2.0 * a[0] * a[0])).sqrt();
+            (1, factor)
+        };
+
+        for i in first..half {
+            a[i] = -m[i] / factor;
+        }
+    }
+
+    let mean = sorted.iter().sum::<f64>() / length;
+    let total: f64 = sorted.iter().map(|x| (x - mean) * (x - mean)).sum();
+    let numerator: f64 = a
+        .iter()
+        .enumerate()
+        .map(|(i, a)| a * (sorted[n - 1 - i] - sorted[i]))
+        .sum();
+    let statistic = (numerator * numerator / total).min(1.0);
+
+    let p_value = if n == 3 {
+        (6.0 / PI * (statistic.sqrt().asin() - (0.75_f64).sqrt().asin())).max(0.0)
+    } else {
+        let y = (1.0 - statistic).ln();
+        let (z, mean, deviation) = if n <= 11 {
+            let gamma = polynomial(&G, length);
+            if y >= gamma {
+                return TestResult {
+                    statistic,
+                    p_value: 0.0,
+                };
+            }
+            (
+                -(gamma - y).ln(),
+                polynomial(&C3, length),
+                polynomial(&C4, length).exp(),
+            )
+        } else {
+            let ln = length.ln();
+            (y, polynomial(&C5, ln), polynomial(&C6, ln).exp())
+        };
+
+        1.0 - normal_cdf((z - mean) / deviation)
+    };
+
+    TestResult { statistic, p_value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Inference, MultiFrame};
+
+    #[test]
+    fn shapiro_wilk_test() {
+        // Weights of 11 men from Shapiro and Wilk (1965); R's shapiro.test
+        // gives W = 0.78881, p = 0.006704
+        let weights = [
+            148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0,
+        ];
+
+        let result = shapiro_wilk(&weights);
+
+        assert!(f64::abs(result.statistic - 0.78881) < 1e-5);
+        assert!(f64::abs(result.p_value - 0.006704) < 1e-6);
+
+        // Evenly spaced values are close to normal
+        let spaced: Vec<f64> = (0..30)
+            .map(|i| normal_quantile((i as f64 + 0.5) / 30.0))
+            .collect();
+        let result = shapiro_wilk(&spaced);
+        assert!(result.statistic > 0.98);
+        assert!(result.p_value > 0.5);
+
+        assert!(shapiro_wilk(&[1.0]).statistic.is_nan());
+        assert!(shapiro_wilk(&[]).p_value.is_nan());
+    }
+
+    #[test]
+    fn residual_tests() {
+        let residuals = [1.0, -1.0, 2.0, -2.0, 1.0, -1.0];
+
+        // Σ(Δe)² = 4 + 9 + 16 + 9 + 4 = 42, Σe² = 12
+        let dw = durbin_watson(&residuals);
+        assert!(f64::abs(dw.statistic - 3.5) < 1e-12);
+
+        // Symmetric residuals have no skewness, only excess kurtosis counts
+        let jb = jarque_bera(&residuals);
+        let kurtosis = (1.0 + 1.0 + 16.0 + 16.0 + 1.0 + 1.0) / 6.0 / (2.0 * 2.0);
+        assert!(f64::abs(jb.statistic - (kurtosis - 3.0_f64).powi(2) / 4.0) < 1e-12);
+
+        // ρ₁ = (-1 - 2 - 4 - 2 - 1) / 12
+        let lb = ljung_box(&residuals, 1);
+        let rho = -10.0 / 12.0;
+        assert!(f64::abs(lb.statistic - 6.0 * 8.0 * rho * rho / 5.0) < 1e-12);
+        assert!(lb.p_value < 0.05);
+        assert!(ljung_box(&residuals, 6).statistic.is_nan());
+        assert!(ljung_box(&residuals, 10).p_value.is_nan());
+    }
+
+    #[test]
+    fn heteroskedasticity_tests() {
+        // Noise that grows with x
+        let x: Vec<f64> = (1..=40).map(|i| i as f64).collect();
+        let noise = |i: usize| if i.is_multiple_of(2) { 1.0 } else { -1.0 };
+        let mut frame = MultiFrame {
+            y: x.iter()
+                .enumerate()
+                .map(|(i, x)| 2.0 * x + 1.0 + noise(i) * x * 0.5)
+                .collect(),
+            x: x.iter().map(|x| vec![*x]).collect(),
+            weights: None,
+            verbose: false,
+        };
+        let fit = frame.inference();
+
+        let bp = fit.breusch_pagan();
+        let white = fit.white();
+        assert!(bp.p_value < 0.01);
+        assert!(white.p_value < 0.01);
+        assert!(white.statistic >= bp.statistic);
+
+        // Constant noise shows no heteroskedasticity
+        frame.y = x
+            .iter()
+            .enumerate()
+            .map(|(i, x)| 2.0 * x + 1.0 + noise(i))
+            .collect();
+        assert!(frame.inference().breusch_pagan().p_value > 0.5);
+    }
+}
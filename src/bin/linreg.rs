@@ -0,0 +1,500 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use linear_regression_rs::csv::{CsvOptions, Missing};
+use linear_regression_rs::optimizer::Adam;
+use linear_regression_rs::{
+    ElasticNet, FitMetadata, FittedModel, LinearFit, MultiRegression, RegressionError, Ridge,
+    Stopping, Train, Trainer, Transform,
+};
+
+const USAGE: &str = "\
+Usage:
+  linreg fit <data.csv> --target <column> [options] [--solver <solver>]
+             [--standardize] [--format json|bincode] [--output <model>]
+  linreg predict <model> <data.csv> [--delimiter <char>] [--output <file>]
+                 (rows with missing values are an error)
+  linreg report <data.csv> --target <column> [options] [--markdown]
+
+Data options:
+  --features <a,b,...>   feature columns, every other column by default
+  --weights <column>     per-sample weights
+  --delimiter <char>     cell delimiter, ',' by default
+  --missing <policy>     error, skip, mean or median, error by default
+
+Solvers:
+  least-squares          closed form, the default
+  gradient-descent       Adam with --epochs (1000) and --learning-rate (0.01)
+  ridge, lasso           penalized fits with --lambda (1.0)
+
+Columns are header names or zero-based positions.";
+
+/// Options that take no value.
+const FLAGS: [&str; 2] = ["standardize", "markdown"];
+
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(flag) if FLAGS.contains(&flag) => {
+                    options.insert(flag.to_string(), String::new());
+                }
+                Some(name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("--{} needs a value", name))?;
+                    options.insert(name.to_string(), value);
+                }
+                None => positional.push(arg),
+            }
+        }
+
+        Ok(Args {
+            positional,
+            options,
+        })
+    }
+
+    fn positional(&self, index: usize, name: &str) -> Result<&str, String> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| format!("missing {}", name))
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("invalid value {:?} for --{}", value, name)),
+            None => Ok(default),
+        }
+    }
+
+    /// Where the output goes, stdout unless `--output` names a file.
+    fn output(&self) -> io::Result<Box<dyn Write>> {
+        Ok(match self.get("output") {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(io::stdout()),
+        })
+    }
+}
+
+/// Loader settings shared by the subcommands, for the given response column.
+fn csv_options(args: &Args, target: &str) -> Result<CsvOptions, String> {
+    let column = |name: &str| -> linear_regression_rs::csv::Column {
+        match name.parse::<usize>() {
+            Ok(index) => index.into(),
+            Err(_) => name.into(),
+        }
+    };
+
+    let mut options = CsvOptions::new(column(target));
+    if let Some(features) = args.get("features") {
+        options = options.with_features(features.split(',').map(column));
+    }
+    if let Some(weights) = args.get("weights") {
+        options = options.with_weights(column(weights));
+    }
+    if let Some(delimiter) = args.get("delimiter") {
+        let mut chars = delimiter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(delimiter), None) => options = options.with_delimiter(delimiter),
+            _ => return Err(format!("invalid delimiter {:?}", delimiter)),
+        }
+    }
+    let missing = match args.get("missing").unwrap_or("error") {
+        "error" => Missing::Error,
+        "skip" => Missing::SkipRow,
+        "mean" => Missing::Mean,
+        "median" => Missing::Median,
+        other => return Err(format!("unknown missing value policy {:?}", other)),
+    };
+
+    Ok(options.with_missing(missing))
+}
+
+fn fit(args: &Args) -> Result<(), Box<dyn Error>> {
+    let path = args.positional(1, "data file")?;
+    let target = args.get("target").ok_or("missing --target")?;
+
+    let data = csv_options(args, target)?.read_path(path)?;
+    let (features, target) = (data.features.clone(), data.target.clone());
+    let weighted = data.weights.is_some();
+    let fills = data.fills.clone();
+    let mut frame = data.into_multi_frame()?;
+
+    // Gaps at prediction time are filled the way they were during the fit
+    let mut preprocessing: Vec<Transform> = fills
+        .map(|values| Transform::Impute { values })
+        .into_iter()
+        .collect();
+    if args.flag("standardize") {
+        let transform = Transform::standardize(&frame.x);
+        frame.x = frame.x.iter().map(|x| transform.apply(x)).collect();
+        preprocessing.push(transform);
+    }
+
+    let solver = args.get("solver").unwrap_or("least-squares");
+    let lambda = args.number("lambda", 1.0)?;
+    let (coefficients, b, epochs) = match solver {
+        "least-squares" => {
+            let (coefficients, b) = frame.try_least_squares()?;
+            (coefficients, b, None)
+        }
+        "gradient-descent" => {
+            let mut trainer = Trainer::new(args.number("learning-rate", 0.01)?)
+                .with_optimizer(Adam::default())
+                .with_stopping(Stopping {
+                    max_epochs: args.number("epochs", 1000)?,
+                    ..Stopping::default()
+                });
+            let result = frame.train(&mut trainer)?;
+            if !result.converged() {
+                eprintln!(
+                    "warning: stopped after {} epochs ({:?})",
+                    result.epochs, result.reason
+                );
+            }
+            (result.coefficients, result.b, Some(result.epochs))
+        }
+        "ridge" => {
+            let (coefficients, b) = frame.ridge_least_squares(lambda);
+            (coefficients, b, None)
+        }
+        "lasso" => {
+            let (coefficients, b) = frame.lasso(lambda);
+            (coefficients, b, None)
+        }
+        other => return Err(format!("unknown solver {:?}", other).into()),
+    };
+    if !b.is_finite() || coefficients.iter().any(|c| !c.is_finite()) {
+        return Err(RegressionError::Singular.into());
+    }
+
+    let predict = |x: &[f64]| coefficients.iter().zip(x).map(|(c, x)| c * x).sum::<f64>() + b;
+    let metadata = FitMetadata {
+        solver: solver.to_string(),
+        samples: frame.y.len(),
+        epochs,
+        mean_squared_error: Some(frame.mean_squared_error(&predict)),
+        r_squared: Some(frame.r_squared(&predict)),
+        weighted,
+        ..FitMetadata::default()
+    };
+
+    let model = preprocessing.into_iter().fold(
+        FittedModel::new(features, coefficients, b)
+            .with_target(&target)
+            .with_metadata(metadata),
+        FittedModel::with_preprocessing,
+    );
+
+    let mut output = args.output()?;
+    match args.get("format").unwrap_or("json") {
+        "json" => writeln!(output, "{}", model.to_json()?)?,
+        "bincode" => output.write_all(&model.to_bincode()?)?,
+        other => return Err(format!("unknown format {:?}", other).into()),
+    }
+
+    Ok(())
+}
+
+fn predict(args: &Args) -> Result<(), Box<dyn Error>> {
+    let bytes = fs::read(args.positional(1, "model file")?)?;
+    let model = match FittedModel::from_bincode(&bytes) {
+        Ok(model) => model,
+        Err(_) => FittedModel::from_json(std::str::from_utf8(&bytes)?)?,
+    };
+    if model.features.is_empty() {
+        return Err("the model has no features".into());
+    }
+    // Every input row needs its prediction on the matching output line, so no
+    // row may be dropped or filled in
+    if let Some(missing) = args.get("missing").filter(|missing| *missing != "error") {
+        return Err(format!(
+            "predict writes one line per input row and does not support --missing {}",
+            missing
+        )
+        .into());
+    }
+
+    // The data has no response column; reading the first feature in its place
+    // keeps the loader's column handling
+    let options = csv_options(args, &model.features[0])?
+        .with_features(model.features.iter().map(String::as_str));
+    let data = options.read_path(args.positional(2, "data file")?)?;
+
+    let mut output = args.output()?;
+    writeln!(output, "{}", model.target)?;
+    for x in &data.x {
+        writeln!(output, "{}", model.predict(x))?;
+    }
+
+    Ok(())
+}
+
+fn report(args: &Args) -> Result<(), Box<dyn Error>> {
+    let path = args.positional(1, "data file")?;
+    let target = args.get("target").ok_or("missing --target")?;
+
+    let data = csv_options(args, target)?.read_path(path)?;
+    let names = data.features.clone();
+    let mut frame = data.into_multi_frame()?;
+
+    // A design without a unique fit has no meaningful table to report
+    let (coefficients, b) = frame.try_least_squares()?;
+    let fit = LinearFit::weighted(&frame.x, &frame.y, &frame.weights, &coefficients, b);
+    if fit.degrees_of_freedom <= 0.0 {
+        return Err(format!(
+            "{} observations leave no residual degrees of freedom for {} parameters",
+            frame.y.len(),
+            coefficients.len() + 1
+        )
+        .into());
+    }
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let summary = fit.summary().with_names(&names);
+
+    let mut output = args.output()?;
+    if args.flag("markdown") {
+        writeln!(output, "{}", summary.to_markdown())?;
+    } else {
+        writeln!(output, "{}", summary)?;
+    }
+
+    writeln!(output, "Residual diagnostics")?;
+    for (name, test) in [
+        ("Breusch-Pagan", fit.breusch_pagan()),
+        ("Durbin-Watson", fit.durbin_watson()),
+        ("Jarque-Bera", fit.jarque_bera()),
+    ] {
+        writeln!(
+            output,
+            "{:<16}{:>12.4}  p = {:.4}",
+            name, test.statistic, test.p_value
+        )?;
+    }
+
+    let flags = fit.influence().flagged();
+    writeln!(output)?;
+    writeln!(output, "Influential observations (zero-based rows)")?;
+    for (name, rows) in [
+        ("Leverage", flags.leverage),
+        ("Outliers", flags.outliers),
+        ("Cook's distance", flags.cooks_distance),
+    ] {
+        writeln!(output, "{:<16}{:?}", name, rows)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match args.positional.first().map(String::as_str) {
+        Some("fit") => fit(&args),
+        Some("predict") => predict(&args),
+        Some("report") => report(&args),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Args {
+        Args::parse(line.split_whitespace().map(String::from)).unwrap()
+    }
+
+    #[test]
+    fn args_test() {
+        let parsed = args("fit data.csv --target y --standardize --solver ridge");
+
+        assert_eq!(parsed.positional, vec!["fit", "data.csv"]);
+        assert_eq!(parsed.get("target"), Some("y"));
+        assert_eq!(parsed.get("solver"), Some("ridge"));
+        assert!(parsed.flag("standardize"));
+        assert!(!parsed.flag("markdown"));
+        assert_eq!(parsed.number("lambda", 1.0), Ok(1.0));
+
+        assert!(Args::parse(["--target".to_string()]).is_err());
+        assert!(csv_options(&args("--missing often"), "y").is_err());
+    }
+
+    #[test]
+    fn fit_predict_test() {
+        let directory = std::env::temp_dir().join(format!("linreg-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let data = directory.join("data.csv");
+        let model = directory.join("model.bin");
+        let predictions = directory.join("predictions.csv");
+
+        fs::write(&data, "a,b,y\n1,2,3\n2,1,6\n3,4,5\n4,3,8\n5,5,8\n0,1,2\n").unwrap();
+
+        let command = |line: &str| {
+            args(
+                &line
+                    .replace("DATA", data.to_str().unwrap())
+                    .replace("MODEL", model.to_str().unwrap())
+                    .replace("PREDICTIONS", predictions.to_str().unwrap()),
+            )
+        };
+
+        fit(&command(
+            "fit DATA --target y --standardize --format bincode --output MODEL",
+        ))
+        .unwrap();
+        predict(&command("predict MODEL DATA --output PREDICTIONS")).unwrap();
+        assert!(predict(&command("predict MODEL DATA --missing skip")).is_err());
+
+        // y = 2a - b + 3 exactly, so predictions reproduce the response
+        let predicted: Vec<f64> = fs::read_to_string(&predictions)
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|line| line.parse().unwrap())
+            .collect();
+        for (predicted, y) in predicted.iter().zip([3.0, 6.0, 5.0, 8.0, 8.0, 2.0]) {
+            assert!(f64::abs(predicted - y) < 1e-9);
+        }
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn impute_test() {
+        let directory = std::env::temp_dir().join(format!("linreg-impute-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let data = directory.join("data.csv");
+        let model = directory.join("model.json");
+
+        fs::write(&data, "a,y\n1,3\n,5\n3,7\n5,11\n").unwrap();
+
+        fit(&args(&format!(
+            "fit {} --target y --missing mean --standardize --output {}",
+            data.display(),
+            model.display()
+        )))
+        .unwrap();
+        let model = FittedModel::from_json(&fs::read_to_string(&model).unwrap()).unwrap();
+
+        // The fill comes first so standardizing sees the imputed value
+        assert_eq!(
+            model.preprocessing[0],
+            Transform::Impute { values: vec![3.0] }
+        );
+        assert!(matches!(
+            model.preprocessing[1],
+            Transform::Standardize { .. }
+        ));
+        assert_eq!(model.predict(&[f64::NAN]), model.predict(&[3.0]));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn headerless_test() {
+        let directory =
+            std::env::temp_dir().join(format!("linreg-headerless-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let data = directory.join("data.csv");
+        let model = directory.join("model.json");
+        let predictions = directory.join("predictions.csv");
+
+        fs::write(&data, "1,2,3\n2,1,6\n3,4,5\n4,3,8\n").unwrap();
+
+        fit(&args(&format!(
+            "fit {} --target 2 --output {}",
+            data.display(),
+            model.display()
+        )))
+        .unwrap();
+        // The model knows the features as x0 and x1
+        predict(&args(&format!(
+            "predict {} {} --output {}",
+            model.display(),
+            data.display(),
+            predictions.display()
+        )))
+        .unwrap();
+
+        let lines = fs::read_to_string(&predictions).unwrap();
+        let lines: Vec<&str> = lines.lines().collect();
+        assert_eq!(lines[0], "x2");
+        assert_eq!(lines.len(), 5);
+        assert!(f64::abs(lines[2].parse::<f64>().unwrap() - 6.0) < 1e-9);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn report_test() {
+        let data = std::env::temp_dir().join(format!("linreg-report-{}.csv", std::process::id()));
+        // b is twice a
+        fs::write(&data, "a,b,y\n1,2,3\n2,4,5\n3,6,1\n4,8,2\n").unwrap();
+
+        let result = report(&args(&format!("report {} --target y", data.display())));
+
+        fs::remove_file(&data).unwrap();
+        let error = result.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<RegressionError>(),
+            Some(&RegressionError::Singular)
+        );
+    }
+
+    #[test]
+    fn saturated_report_test() {
+        let data =
+            std::env::temp_dir().join(format!("linreg-saturated-{}.csv", std::process::id()));
+        fs::write(&data, "x,y\n1,3\n2,5\n").unwrap();
+
+        let result = report(&args(&format!("report {} --target y", data.display())));
+
+        fs::remove_file(&data).unwrap();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "2 observations leave no residual degrees of freedom for 2 parameters"
+        );
+    }
+}
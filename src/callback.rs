@@ -0,0 +1,151 @@
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+/// State of training after one epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochRecord {
+    pub epoch: i32,
+    pub coefficients: Vec<f64>,
+    pub b: f64,
+    /// Mean squared error over the whole frame.
+    pub loss: f64,
+    /// Norm of the gradient, see `Stopping::gradient_tolerance`.
+    pub gradient_norm: f64,
+    /// Learning rate after the schedule was applied.
+    pub learning_rate: f64,
+}
+
+/// What a callback asks training to do next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Stop,
+}
+
+/// Observer invoked by `Train::train` after every epoch. Closures taking an
+/// `&EpochRecord` and returning a `Control` are callbacks too.
+pub trait Callback {
+    fn on_epoch(&mut self, record: &EpochRecord) -> Control;
+}
+
+impl<F: FnMut(&EpochRecord) -> Control> Callback for F {
+    fn on_epoch(&mut self, record: &EpochRecord) -> Control {
+        self(record)
+    }
+}
+
+/// Writes one line per epoch to any writer. Verbose frames log to stdout.
+pub struct Logger<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> Logger<W> {
+    pub fn new(writer: W) -> Self {
+        Logger { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl Logger<io::Stdout> {
+    pub fn stdout() -> Self {
+        Logger::new(io::stdout())
+    }
+}
+
+impl<W: Write> Callback for Logger<W> {
+    fn on_epoch(&mut self, record: &EpochRecord) -> Control {
+        // A failing log must not abort an otherwise healthy fit
+        let _ = writeln!(
+            self.writer,
+            "Epoch: {} loss: {} gradient norm: {} y = {:?} · x + {}",
+            record.epoch, record.loss, record.gradient_norm, record.coefficients, record.b
+        );
+
+        Control::Continue
+    }
+}
+
+/// Every epoch of a fit, in order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct History {
+    pub records: Vec<EpochRecord>,
+}
+
+impl History {
+    pub fn losses(&self) -> Vec<f64> {
+        self.records.iter().map(|record| record.loss).collect()
+    }
+
+    /// One row per epoch with a header; coefficients are named `x0`, `x1`, ….
+    pub fn to_csv(&self) -> String {
+        let features = self
+            .records
+            .first()
+            .map_or(0, |record| record.coefficients.len());
+
+        let mut csv = String::from("epoch,loss,gradient_norm,learning_rate,b");
+        for j in 0..features {
+            let _ = write!(csv, ",x{}", j);
+        }
+        csv.push('\n');
+
+        for record in &self.records {
+            let _ = write!(
+                csv,
+                "{},{},{},{},{}",
+                record.epoch, record.loss, record.gradient_norm, record.learning_rate, record.b
+            );
+            for c in &record.coefficients {
+                let _ = write!(csv, ",{}", c);
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(epoch: i32) -> EpochRecord {
+        EpochRecord {
+            epoch,
+            coefficients: vec![1.5, -2.0],
+            b: 0.5,
+            loss: 0.25,
+            gradient_norm: 0.125,
+            learning_rate: 0.01,
+        }
+    }
+
+    #[test]
+    fn history_test() {
+        let history = History {
+            records: vec![record(0), record(1)],
+        };
+
+        assert_eq!(history.losses(), vec![0.25, 0.25]);
+        assert_eq!(
+            history.to_csv(),
+            "epoch,loss,gradient_norm,learning_rate,b,x0,x1\n\
+             0,0.25,0.125,0.01,0.5,1.5,-2\n\
+             1,0.25,0.125,0.01,0.5,1.5,-2\n"
+        );
+    }
+
+    #[test]
+    fn logger_test() {
+        let mut logger = Logger::new(Vec::new());
+
+        assert_eq!(logger.on_epoch(&record(3)), Control::Continue);
+        assert_eq!(
+            String::from_utf8(logger.into_inner()).unwrap(),
+            "Epoch: 3 loss: 0.25 gradient norm: 0.125 y = [1.5, -2.0] · x + 0.5\n"
+        );
+    }
+}
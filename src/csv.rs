@@ -0,0 +1,516 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::{LinearFrame, MultiFrame, RegressionError};
+
+/// A column of the file, by header name or zero-based position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    Name(String),
+    Index(usize),
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.to_string())
+    }
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Column::Name(name) => write!(f, "{}", name),
+            Column::Index(index) => write!(f, "#{}", index),
+        }
+    }
+}
+
+/// Whether the first record names the columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Header {
+    /// A first record with any non-numeric cell is a header.
+    #[default]
+    Detect,
+    Present,
+    Absent,
+}
+
+/// What to do with empty, `NA`, unparsable or non-finite cells.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Missing {
+    #[default]
+    Error,
+    SkipRow,
+    /// The imputing policies fill in feature cells only; rows with a missing
+    /// response or weight are skipped.
+    Mean,
+    Median,
+    Constant(f64),
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    /// A selected column is not in the header, or for a file without one,
+    /// not one of the generated `x0`, `x1`, … names.
+    UnknownColumn(Column),
+    /// The cell on the 1-based `line` could not be used.
+    InvalidCell {
+        line: usize,
+        column: String,
+        value: String,
+    },
+    /// A header was required but the input has no lines.
+    MissingHeader,
+    /// A `LinearFrame` needs exactly one feature.
+    FeatureCount(usize),
+    Regression(RegressionError),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(error) => write!(f, "{}", error),
+            CsvError::UnknownColumn(column) => write!(f, "unknown column {}", column),
+            CsvError::InvalidCell {
+                line,
+                column,
+                value,
+            } => write!(
+                f,
+                "line {}: invalid value {:?} in column {}",
+                line, value, column
+            ),
+            CsvError::MissingHeader => write!(f, "the input has no header line"),
+            CsvError::FeatureCount(found) => {
+                write!(f, "expected a single feature column but found {}", found)
+            }
+            CsvError::Regression(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for CsvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CsvError::Io(error) => Some(error),
+            CsvError::Regression(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> Self {
+        CsvError::Io(error)
+    }
+}
+
+impl From<RegressionError> for CsvError {
+    fn from(error: RegressionError) -> Self {
+        CsvError::Regression(error)
+    }
+}
+
+/// Which columns to read and how.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    pub target: Column,
+    /// Feature columns in order; every column but the target and the weights
+    /// when empty.
+    pub features: Vec<Column>,
+    pub weights: Option<Column>,
+    pub delimiter: char,
+    pub header: Header,
+    pub missing: Missing,
+}
+
+/// Columns read from a file, with their names. Columns of a file without a
+/// header are named `x0`, `x1`, … after their position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dataset {
+    pub target: String,
+    pub features: Vec<String>,
+    pub x: Vec<Vec<f64>>,
+    pub y: Vec<f64>,
+    pub weights: Option<Vec<f64>>,
+    /// Values that stood in for missing feature cells, one per feature, when
+    /// the options impute them.
+    pub fills: Option<Vec<f64>>,
+}
+
+impl Dataset {
+    pub fn into_multi_frame(self) -> Result<MultiFrame, CsvError> {
+        let frame = match self.weights {
+            Some(weights) => MultiFrame::weighted(self.x, self.y, weights)?,
+            None => MultiFrame::new(self.x, self.y)?,
+        };
+        Ok(frame)
+    }
+
+    pub fn into_linear_frame(self) -> Result<LinearFrame, CsvError> {
+        if self.features.len() != 1 {
+            return Err(CsvError::FeatureCount(self.features.len()));
+        }
+
+        let x = self.x.into_iter().map(|row| row[0]).collect();
+        let frame = match self.weights {
+            Some(weights) => LinearFrame::weighted(x, self.y, weights)?,
+            None => LinearFrame::new(x, self.y)?,
+        };
+        Ok(frame)
+    }
+}
+
+/// Splits one record, honoring double quoted cells with `""` escapes. Quoted
+/// cells cannot span lines.
+fn split(line: &str, delimiter: char) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell);
+
+    cells
+}
+
+/// Finite value of a cell, `None` when it is missing or unparsable.
+fn parse(cell: Option<&String>) -> Option<f64> {
+    cell.and_then(|cell| cell.trim().parse::<f64>().ok())
+        .filter(|value| value.is_finite())
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+impl CsvOptions {
+    pub fn new(target: impl Into<Column>) -> Self {
+        CsvOptions {
+            target: target.into(),
+            features: Vec::new(),
+            weights: None,
+            delimiter: ',',
+            header: Header::Detect,
+            missing: Missing::Error,
+        }
+    }
+
+    pub fn with_features<C: Into<Column>>(mut self, features: impl IntoIterator<Item = C>) -> Self {
+        self.features = features.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_weights(mut self, weights: impl Into<Column>) -> Self {
+        self.weights = Some(weights.into());
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn with_missing(mut self, missing: Missing) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    pub fn read(&self, reader: impl Read) -> Result<Dataset, CsvError> {
+        // Records with their 1-based line numbers, blank lines dropped
+        let mut records = Vec::new();
+        for (i, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                records.push((i + 1, split(&line, self.delimiter)));
+            }
+        }
+
+        let header = match self.header {
+            Header::Present => true,
+            Header::Absent => false,
+            Header::Detect => records.first().is_some_and(|(_, cells)| {
+                cells.iter().any(|cell| {
+                    let cell = cell.trim();
+                    !cell.is_empty() && cell != "NA" && cell.parse::<f64>().is_err()
+                })
+            }),
+        };
+
+        let width = records.first().map_or(0, |(_, cells)| cells.len());
+        let names: Vec<String> = if header {
+            if records.is_empty() {
+                return Err(CsvError::MissingHeader);
+            }
+            records
+                .remove(0)
+                .1
+                .iter()
+                .map(|name| name.trim().to_string())
+                .collect()
+        } else {
+            (0..width).map(|i| format!("x{}", i)).collect()
+        };
+
+        let resolve = |column: &Column| -> Result<usize, CsvError> {
+            let index = match column {
+                Column::Index(index) => Some(*index).filter(|index| *index < names.len()),
+                // Files without a header match the names they are given in a
+                // `Dataset`, so those round trip through a saved model
+                Column::Name(name) => names.iter().position(|n| n == name),
+            };
+            index.ok_or_else(|| CsvError::UnknownColumn(column.clone()))
+        };
+
+        let target = resolve(&self.target)?;
+        let weights = self.weights.as_ref().map(resolve).transpose()?;
+        let features = if self.features.is_empty() {
+            (0..names.len())
+                .filter(|i| *i != target && Some(*i) != weights)
+                .collect()
+        } else {
+            self.features
+                .iter()
+                .map(resolve)
+                .collect::<Result<Vec<usize>, CsvError>>()?
+        };
+
+        let impute = matches!(
+            self.missing,
+            Missing::Mean | Missing::Median | Missing::Constant(_)
+        );
+        let invalid = |line: usize, index: usize, cells: &[String]| CsvError::InvalidCell {
+            line,
+            column: names[index].clone(),
+            value: cells.get(index).cloned().unwrap_or_default(),
+        };
+
+        let mut x: Vec<Vec<Option<f64>>> = Vec::new();
+        let mut y = Vec::new();
+        let mut w = Vec::new();
+
+        'records: for (line, cells) in &records {
+            let mut required = Vec::with_capacity(2);
+            for index in std::iter::once(target).chain(weights) {
+                match parse(cells.get(index)) {
+                    Some(value) => required.push(value),
+                    None if self.missing == Missing::Error => {
+                        return Err(invalid(*line, index, cells))
+                    }
+                    None => continue 'records,
+                }
+            }
+
+            let mut row = Vec::with_capacity(features.len());
+            for &index in &features {
+                let value = parse(cells.get(index));
+                if value.is_none() && !impute {
+                    if self.missing == Missing::Error {
+                        return Err(invalid(*line, index, cells));
+                    }
+                    continue 'records;
+                }
+                row.push(value);
+            }
+
+            x.push(row);
+            y.push(required[0]);
+            if weights.is_some() {
+                w.push(required[1]);
+            }
+        }
+
+        // Fill values come from the rows that are kept
+        let fills: Vec<f64> = (0..features.len())
+            .map(|j| {
+                let mut present: Vec<f64> = x.iter().filter_map(|row| row[j]).collect();
+                match self.missing {
+                    Missing::Mean => present.iter().sum::<f64>() / present.len() as f64,
+                    Missing::Median if !present.is_empty() => median(&mut present),
+                    Missing::Constant(value) => value,
+                    _ => f64::NAN,
+                }
+            })
+            .collect();
+
+        Ok(Dataset {
+            target: names[target].clone(),
+            features: features.iter().map(|i| names[*i].clone()).collect(),
+            x: x.into_iter()
+                .map(|row| {
+                    row.iter()
+                        .zip(&fills)
+                        .map(|(value, fill)| value.unwrap_or(*fill))
+                        .collect()
+                })
+                .collect(),
+            y,
+            weights: weights.map(|_| w),
+            fills: Some(fills).filter(|_| impute),
+        })
+    }
+
+    pub fn read_path(&self, path: impl AsRef<Path>) -> Result<Dataset, CsvError> {
+        self.read(File::open(path)?)
+    }
+}
+
+impl LinearFrame {
+    /// Loads a single feature and the response from a CSV file.
+    pub fn from_csv(path: impl AsRef<Path>, options: &CsvOptions) -> Result<Self, CsvError> {
+        options.read_path(path)?.into_linear_frame()
+    }
+}
+
+impl MultiFrame {
+    /// Loads the features and the response from a CSV file.
+    pub fn from_csv(path: impl AsRef<Path>, options: &CsvOptions) -> Result<Self, CsvError> {
+        options.read_path(path)?.into_multi_frame()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "\
+height,\"weight, kg\",age,sample weight
+1.5,50,30,1
+1.6,,40,2
+
+1.7,70,NA,1
+1.8,80,50,0.5
+";
+
+    #[test]
+    fn split_test() {
+        assert_eq!(split("a;\"b;c\";\"d\"\"e\"", ';'), vec!["a", "b;c", "d\"e"]);
+        assert_eq!(split("1,,3", ','), vec!["1", "", "3"]);
+    }
+
+    #[test]
+    fn columns_test() {
+        let options = CsvOptions::new("height")
+            .with_features(["age"])
+            .with_weights(3)
+            .with_missing(Missing::SkipRow);
+        let data = options.read(FILE.as_bytes()).unwrap();
+
+        assert_eq!(data.target, "height");
+        assert_eq!(data.features, vec!["age"]);
+        assert_eq!(data.x, vec![vec![30.0], vec![40.0], vec![50.0]]);
+        assert_eq!(data.y, vec![1.5, 1.6, 1.8]);
+        assert_eq!(data.weights, Some(vec![1.0, 2.0, 0.5]));
+
+        let frame = data.into_linear_frame().unwrap();
+        assert_eq!(frame.x, vec![30.0, 40.0, 50.0]);
+
+        // Without a header columns are known by position or generated name
+        let options = CsvOptions::new(1)
+            .with_header(Header::Absent)
+            .with_delimiter(';');
+        let data = options.read("1;2;3\n4;5;6\n".as_bytes()).unwrap();
+        assert_eq!(data.target, "x1");
+        assert_eq!(data.features, vec!["x0", "x2"]);
+        assert_eq!(data.x, vec![vec![1.0, 3.0], vec![4.0, 6.0]]);
+
+        let named = CsvOptions::new("x1")
+            .with_features(data.features.iter().map(String::as_str))
+            .with_delimiter(';');
+        assert_eq!(named.read("1;2;3\n4;5;6\n".as_bytes()).unwrap(), data);
+
+        assert!(matches!(
+            CsvOptions::new("height").read("1,2\n".as_bytes()),
+            Err(CsvError::UnknownColumn(_))
+        ));
+        assert!(matches!(
+            CsvOptions::new(0)
+                .with_header(Header::Present)
+                .read("\n".as_bytes()),
+            Err(CsvError::MissingHeader)
+        ));
+    }
+
+    #[test]
+    fn missing_test() {
+        let options = CsvOptions::new("height").with_features(["weight, kg", "age"]);
+
+        match options.read(FILE.as_bytes()) {
+            Err(CsvError::InvalidCell {
+                line,
+                column,
+                value,
+            }) => {
+                assert_eq!(
+                    (line, column.as_str(), value.as_str()),
+                    (3, "weight, kg", "")
+                );
+            }
+            result => panic!("unexpected {:?}", result),
+        }
+
+        let skipped = options
+            .clone()
+            .with_missing(Missing::SkipRow)
+            .read(FILE.as_bytes())
+            .unwrap();
+        assert_eq!(skipped.y, vec![1.5, 1.8]);
+
+        let mean = options
+            .clone()
+            .with_missing(Missing::Mean)
+            .read(FILE.as_bytes())
+            .unwrap();
+        assert_eq!(mean.x[1], vec![(50.0 + 70.0 + 80.0) / 3.0, 40.0]);
+        assert_eq!(mean.x[2], vec![70.0, 40.0]);
+        assert_eq!(mean.fills, Some(vec![(50.0 + 70.0 + 80.0) / 3.0, 40.0]));
+        assert_eq!(skipped.fills, None);
+
+        let median = options
+            .clone()
+            .with_missing(Missing::Median)
+            .read(FILE.as_bytes())
+            .unwrap();
+        assert_eq!(median.x[1][0], 70.0);
+
+        let constant = options
+            .with_missing(Missing::Constant(0.0))
+            .read(FILE.as_bytes())
+            .unwrap();
+        assert_eq!(constant.x[2], vec![70.0, 0.0]);
+        assert!(constant.into_multi_frame().is_ok());
+    }
+}
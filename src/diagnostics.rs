@@ -0,0 +1,187 @@
+use crate::linalg;
+use crate::LinearFit;
+
+/// Per-observation influence measures of a least squares fit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Influence {
+    /// Diagonal of the hat matrix.
+    pub hat: Vec<f64>,
+    /// Residuals scaled by the overall residual standard error.
+    pub studentized_residuals: Vec<f64>,
+    /// Residuals scaled by the standard error with the observation left out.
+    pub externally_studentized_residuals: Vec<f64>,
+    pub cooks_distance: Vec<f64>,
+    pub dffits: Vec<f64>,
+    /// Scaled change of each parameter, intercept first, when the
+    /// observation is left out.
+    pub dfbetas: Vec<Vec<f64>>,
+}
+
+/// Observations exceeding the usual rule of thumb for each measure.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InfluenceFlags {
+    /// Hat value above `2p / n`.
+    pub leverage: Vec<usize>,
+    /// Externally studentized residual above 2 in absolute value.
+    pub outliers: Vec<usize>,
+    /// Cook's distance above `4 / n`.
+    pub cooks_distance: Vec<usize>,
+    /// |DFFITS| above `2 sqrt(p / n)`.
+    pub dffits: Vec<usize>,
+    /// Any |DFBETAS| above `2 / sqrt(n)`.
+    pub dfbetas: Vec<usize>,
+}
+
+impl LinearFit {
+    pub fn influence(&self) -> Influence {
+        let length = self.y.len() as f64;
+        let parameters = self.design[0].len() as f64;
+        let sse = linalg::dot(&self.residuals, &self.residuals);
+        let s = self.residual_variance.sqrt();
+
+        let mut influence = Influence {
+            hat: Vec::new(),
+            studentized_residuals: Vec::new(),
+            externally_studentized_residuals: Vec::new(),
+            cooks_distance: Vec::new(),
+            dffits: Vec::new(),
+            dfbetas: Vec::new(),
+        };
+
+        for (row, e) in self.design.iter().zip(&self.residuals) {
+            let projected = linalg::multiply(&self.gram_inverse, row);
+            let h = linalg::dot(row, &projected);
+
+            // Residual variance with this observation left out
+            let s_without = ((sse - e * e / (1.0 - h)) / (length - parameters - 1.0)).sqrt();
+
+            let internal = e / (s * (1.0 - h).sqrt());
+            let external = e / (s_without * (1.0 - h).sqrt());
+
+            influence.hat.push(h);
+            influence.studentized_residuals.push(internal);
+            influence.externally_studentized_residuals.push(external);
+            influence
+                .cooks_distance
+                .push(internal * internal * h / (parameters * (1.0 - h)));
+            influence.dffits.push(external * (h / (1.0 - h)).sqrt());
+
+            // β - β₍ᵢ₎ = (XᵀX)⁻¹ xᵢ eᵢ / (1 - hᵢ)
+            influence.dfbetas.push(
+                projected
+                    .iter()
+                    .enumerate()
+                    .map(|(j, p)| p * e / (1.0 - h) / (s_without * self.gram_inverse[j][j].sqrt()))
+                    .collect(),
+            );
+        }
+
+        influence
+    }
+}
+
+impl Influence {
+    pub fn flagged(&self) -> InfluenceFlags {
+        let length = self.hat.len() as f64;
+        let parameters = self.dfbetas.first().map_or(0, |row| row.len()) as f64;
+
+        let above = |values: &[f64], threshold: f64| -> Vec<usize> {
+            values
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.abs() > threshold)
+                .map(|(i, _)| i)
+                .collect()
+        };
+
+        InfluenceFlags {
+            leverage: above(&self.hat, 2.0 * parameters / length),
+            outliers: above(&self.externally_studentized_residuals, 2.0),
+            cooks_distance: above(&self.cooks_distance, 4.0 / length),
+            dffits: above(&self.dffits, 2.0 * (parameters / length).sqrt()),
+            dfbetas: self
+                .dfbetas
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| row.iter().any(|v| v.abs() > 2.0 / length.sqrt()))
+                .map(|(i, _)| i)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Inference, MultiFrame, MultiRegression};
+
+    fn frame() -> MultiFrame {
+        // The last reading is a bad sensor value far from the trend
+        MultiFrame {
+            x: vec![
+                vec![1.0],
+                vec![2.0],
+                vec![3.0],
+                vec![4.0],
+                vec![5.0],
+                vec![6.0],
+                vec![7.0],
+                vec![8.0],
+                vec![9.0],
+                vec![15.0],
+            ],
+            y: vec![2.1, 3.9, 6.2, 8.1, 9.8, 12.2, 13.9, 16.1, 18.0, 10.0],
+            weights: None,
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn influence_matches_leave_one_out() {
+        let mut frame = frame();
+        let fit = frame.inference();
+        let influence = fit.influence();
+
+        assert!(f64::abs(influence.hat.iter().sum::<f64>() - 2.0) < 1e-10);
+
+        for i in 0..frame.x.len() {
+            let mut without = MultiFrame {
+                x: frame.x.clone(),
+                y: frame.y.clone(),
+                weights: None,
+                verbose: false,
+            };
+            let removed = without.x.remove(i);
+            without.y.remove(i);
+
+            let left_out = without.inference();
+            let s_without = left_out.residual_variance.sqrt();
+
+            let slope_change = fit.coefficients[0].estimate - left_out.coefficients[0].estimate;
+            let expected = slope_change / (s_without * fit.gram_inverse[1][1].sqrt());
+            assert!(f64::abs(influence.dfbetas[i][1] - expected) < 1e-9);
+
+            // The externally studentized residual is the t statistic of the
+            // left out prediction error
+            let predicted =
+                left_out.coefficients[0].estimate * removed[0] + left_out.intercept.estimate;
+            let error =
+                (frame.y[i] - predicted) / (s_without * (1.0 + left_out.leverage(&removed)).sqrt());
+            assert!(f64::abs(influence.externally_studentized_residuals[i] - error) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn flagged_test() {
+        let mut frame = frame();
+        let flags = frame.inference().influence().flagged();
+
+        assert_eq!(flags.leverage, vec![9]);
+        assert_eq!(flags.outliers, vec![9]);
+        assert_eq!(flags.cooks_distance, vec![9]);
+        assert_eq!(flags.dffits, vec![9]);
+        assert!(flags.dfbetas.contains(&9));
+
+        let (coefficients, _) = frame.least_squares();
+        assert!(coefficients[0] < 1.5);
+    }
+}
@@ -0,0 +1,358 @@
+// Special functions and distribution functions used for inference. Written
+// out here so the crate keeps zero dependencies.
+
+use std::f64::consts::{PI, SQRT_2};
+
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// Natural log of the gamma function for `x > 0` (Lanczos approximation).
+pub fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        // Reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let sum = LANCZOS[1..]
+        .iter()
+        .enumerate()
+        .fold(LANCZOS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regularized lower incomplete gamma function `P(a, x)`.
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        0.0
+    } else if x < a + 1.0 {
+        gamma_series(a, x)
+    } else {
+        1.0 - gamma_continued_fraction(a, x)
+    }
+}
+
+/// Regularized upper incomplete gamma function `Q(a, x) = 1 - P(a, x)`.
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_series(a, x)
+    } else {
+        gamma_continued_fraction(a, x)
+    }
+}
+
+fn gamma_series(a: f64, x: f64) -> f64 {
+    let mut term = 1.0 / a;
+    let mut sum = term;
+
+    for n in 1..1000 {
+        term *= x / (a + n as f64);
+        sum += term;
+        if term.abs() < sum.abs() * f64::EPSILON {
+            break;
+        }
+    }
+
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+fn gamma_continued_fraction(a: f64, x: f64) -> f64 {
+    // Modified Lentz evaluation
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < f64::EPSILON {
+            break;
+        }
+    }
+
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+pub fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+}
+
+/// Standard normal cumulative distribution function.
+pub fn normal_cdf(x: f64) -> f64 {
+    // erfc(z) = Q(1/2, z²) for z >= 0
+    let z = x / SQRT_2;
+    if z < 0.0 {
+        0.5 * gamma_q(0.5, z * z)
+    } else {
+        1.0 - 0.5 * gamma_q(0.5, z * z)
+    }
+}
+
+/// Inverse of `normal_cdf`, using Acklam's rational approximation polished
+/// with one Halley step.
+pub fn normal_quantile(p: f64) -> f64 {
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    let x = if p < 0.02425 {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - 0.02425 {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    };
+
+    let e = normal_cdf(x) - p;
+    let u = e * (2.0 * PI).sqrt() * (x * x / 2.0).exp();
+    x - u / (1.0 + x * u / 2.0)
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+pub fn beta_regularized(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    // The continued fraction converges quickly below the mean, use the
+    // symmetry I_x(a, b) = 1 - I_(1-x)(b, a) above it
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    // Modified Lentz evaluation
+    let tiny = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < tiny {
+        d = tiny;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..1000 {
+        let m = m as f64;
+
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+
+        if (d * c - 1.0).abs() < f64::EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+/// Cumulative distribution function of Student's t with `df` degrees of
+/// freedom.
+pub fn student_t_cdf(t: f64, df: f64) -> f64 {
+    if t.is_nan() {
+        return f64::NAN;
+    }
+
+    let tail = 0.5 * beta_regularized(df / (df + t * t), df / 2.0, 0.5);
+    if t > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Two-sided p-value of a t statistic.
+pub fn student_t_p_value(t: f64, df: f64) -> f64 {
+    beta_regularized(df / (df + t * t), df / 2.0, 0.5)
+}
+
+/// Inverse of `student_t_cdf`, found by bisection.
+pub fn student_t_quantile(p: f64, df: f64) -> f64 {
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    if p < 0.5 {
+        return -student_t_quantile(1.0 - p, df);
+    }
+
+    let mut low = 0.0;
+    let mut high = 1.0;
+    while student_t_cdf(high, df) < p {
+        low = high;
+        high *= 2.0;
+    }
+
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if student_t_cdf(mid, df) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+
+        if high - low <= f64::EPSILON * high {
+            break;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+/// Survival function `P(F > f)` of the F distribution with `d1` and `d2`
+/// degrees of freedom.
+pub fn f_p_value(f: f64, d1: f64, d2: f64) -> f64 {
+    if f.is_nan() {
+        return f64::NAN;
+    }
+    if f <= 0.0 {
+        return 1.0;
+    }
+
+    beta_regularized(d2 / (d2 + d1 * f), d2 / 2.0, d1 / 2.0)
+}
+
+/// Survival function `P(X > x)` of the chi-squared distribution with `df`
+/// degrees of freedom.
+pub fn chi_squared_p_value(x: f64, df: f64) -> f64 {
+    gamma_q(df / 2.0, x / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ln_gamma_test() {
+        assert!(f64::abs(ln_gamma(1.0)) < 1e-14);
+        assert!(f64::abs(ln_gamma(5.0) - 24.0_f64.ln()) < 1e-13);
+        assert!(f64::abs(ln_gamma(0.5) - PI.sqrt().ln()) < 1e-13);
+    }
+
+    #[test]
+    fn normal_test() {
+        assert!(f64::abs(normal_cdf(0.0) - 0.5) < 1e-15);
+        assert!(f64::abs(normal_cdf(1.96) - 0.975_002_104_851_780) < 1e-12);
+        assert!(f64::abs(normal_cdf(-3.0) - 0.001_349_898_031_630_094_6) < 1e-14);
+
+        for p in [1e-8, 0.01, 0.3, 0.5, 0.9, 0.975, 1.0 - 1e-6] {
+            assert!(f64::abs(normal_cdf(normal_quantile(p)) - p) < 1e-13);
+        }
+    }
+
+    #[test]
+    fn gamma_test() {
+        // P(1, x) = 1 - e^-x
+        assert!(f64::abs(gamma_p(1.0, 2.0) - (1.0 - (-2.0_f64).exp())) < 1e-14);
+        assert!(f64::abs(gamma_p(3.0, 10.0) + gamma_q(3.0, 10.0) - 1.0) < 1e-14);
+        // Chi-squared with 2 degrees of freedom is exponential with mean 2
+        assert!(f64::abs(chi_squared_p_value(3.0, 2.0) - (-1.5_f64).exp()) < 1e-14);
+    }
+
+    #[test]
+    fn student_t_test() {
+        // df = 1 is the Cauchy distribution
+        assert!(f64::abs(student_t_cdf(1.0, 1.0) - 0.75) < 1e-14);
+        assert!(f64::abs(student_t_cdf(2.0, 5.0) - 0.949_030_260_585_070_7) < 1e-12);
+        assert!(f64::abs(student_t_p_value(-2.0, 5.0) - 0.101_939_478_829_858_6) < 1e-12);
+        assert!(f64::abs(student_t_quantile(0.975, 10.0) - 2.228_138_851_986_274) < 1e-12);
+        assert!(f64::abs(student_t_quantile(0.025, 3.0) + 3.182_446_305_284_263) < 1e-12);
+    }
+
+    #[test]
+    fn f_test() {
+        // F(2, 2) has survival function 1 / (1 + f)
+        assert!(f64::abs(f_p_value(3.0, 2.0, 2.0) - 0.25) < 1e-14);
+        assert_eq!(f_p_value(0.0, 3.0, 7.0), 1.0);
+    }
+}
@@ -0,0 +1,300 @@
+use std::iter::zip;
+
+use crate::linalg;
+use crate::multi::{means, total_weight, weight};
+use crate::penalty::PathStep;
+use crate::trace::{epoch, fit_span};
+use crate::{LinearFrame, MultiFrame};
+
+const MAX_SWEEPS: i32 = 10_000;
+const TOLERANCE: f64 = 1e-12;
+
+/// Sparse regression with L1 and elastic-net penalties, minimizing
+/// `mean squared error + lambda · (l1_ratio · Σ |β| + (1 - l1_ratio) · Σ β²)`
+/// by cyclic coordinate descent.
+pub trait ElasticNet {
+    fn coordinate_descent(
+        &mut self,
+        coefficients: &[f64],
+        lambda: f64,
+        l1_ratio: f64,
+        epoch: i32,
+        tolerance: f64,
+    ) -> (Vec<f64>, f64);
+    fn lasso(&mut self, lambda: f64) -> (Vec<f64>, f64);
+    fn elastic_net(&mut self, lambda: f64, l1_ratio: f64) -> (Vec<f64>, f64);
+    fn elastic_net_path(&mut self, lambdas: &[f64], l1_ratio: f64) -> Vec<PathStep>;
+    fn lambda_max(&mut self, l1_ratio: f64) -> f64;
+}
+
+pub(crate) fn soft_threshold(value: f64, threshold: f64) -> f64 {
+    if value > threshold {
+        value - threshold
+    } else if value < -threshold {
+        value + threshold
+    } else {
+        0.0
+    }
+}
+
+/// Centered features stored column by column, so each coordinate update only
+/// walks one contiguous column. Values are scaled by the square root of their
+/// sample weight, so plain dot products give weighted sums.
+fn centered_columns(frame: &MultiFrame) -> (Vec<Vec<f64>>, Vec<f64>, Vec<f64>, f64) {
+    let (x_means, y_mean) = means(&frame.x, &frame.y, &frame.weights);
+    let root = |i: usize| weight(&frame.weights, i).sqrt();
+
+    let columns = x_means
+        .iter()
+        .enumerate()
+        .map(|(j, mean)| {
+            frame
+                .x
+                .iter()
+                .enumerate()
+                .map(|(i, row)| (row[j] - mean) * root(i))
+                .collect()
+        })
+        .collect();
+    let response = frame
+        .y
+        .iter()
+        .enumerate()
+        .map(|(i, y)| (y - y_mean) * root(i))
+        .collect();
+
+    (columns, response, x_means, y_mean)
+}
+
+impl ElasticNet for MultiFrame {
+    fn coordinate_descent(
+        &mut self,
+        coefficients: &[f64],
+        lambda: f64,
+        l1_ratio: f64,
+        epoch: i32,
+        tolerance: f64,
+    ) -> (Vec<f64>, f64) {
+        fit_span!(
+            "coordinate_descent",
+            samples = self.x.len(),
+            features = self.features(),
+            lambda,
+            l1_ratio
+        );
+
+        let length = total_weight(&self.weights, self.x.len());
+        let (columns, response, x_means, y_mean) = centered_columns(self);
+
+        let mut coefficients = coefficients.to_vec();
+        let mut residuals = response;
+        for (column, c) in zip(&columns, &coefficients) {
+            for (r, x) in zip(&mut residuals, column) {
+                *r -= x * c;
+            }
+        }
+
+        let scales: Vec<f64> = columns
+            .iter()
+            .map(|column| linalg::dot(column, column) / length)
+            .collect();
+
+        for x in 0..epoch {
+            let mut largest_change: f64 = 0.0;
+
+            for (j, column) in columns.iter().enumerate() {
+                let old = coefficients[j];
+
+                // Correlation of the feature with the partial residual that
+                // excludes its own contribution
+                let rho = linalg::dot(column, &residuals) / length + scales[j] * old;
+                let new = soft_threshold(rho, lambda * l1_ratio / 2.0)
+                    / (scales[j] + lambda * (1.0 - l1_ratio));
+                let new = if new.is_finite() { new } else { 0.0 };
+
+                if new != old {
+                    for (r, x) in zip(&mut residuals, column) {
+                        *r -= x * (new - old);
+                    }
+                    coefficients[j] = new;
+                    largest_change = largest_change.max((new - old).abs());
+                }
+            }
+
+            epoch!(sweep = x, ?coefficients, largest_change);
+
+            if largest_change <= tolerance {
+                break;
+            }
+        }
+
+        let b = y_mean - linalg::dot(&coefficients, &x_means);
+
+        (coefficients, b)
+    }
+
+    fn lasso(&mut self, lambda: f64) -> (Vec<f64>, f64) {
+        self.elastic_net(lambda, 1.0)
+    }
+
+    fn elastic_net(&mut self, lambda: f64, l1_ratio: f64) -> (Vec<f64>, f64) {
+        let start = vec![0.0; self.features()];
+        self.coordinate_descent(&start, lambda, l1_ratio, MAX_SWEEPS, TOLERANCE)
+    }
+
+    fn elastic_net_path(&mut self, lambdas: &[f64], l1_ratio: f64) -> Vec<PathStep> {
+        let mut lambdas = lambdas.to_vec();
+        lambdas.sort_by(|a, b| b.total_cmp(a));
+
+        // Each fit starts from the previous, larger lambda's solution, which
+        // is usually only a few sweeps away
+        let mut coefficients = vec![0.0; self.features()];
+
+        lambdas
+            .into_iter()
+            .map(|lambda| {
+                let b;
+                (coefficients, b) =
+                    self.coordinate_descent(&coefficients, lambda, l1_ratio, MAX_SWEEPS, TOLERANCE);
+                PathStep::new(lambda, coefficients.clone(), b)
+            })
+            .collect()
+    }
+
+    fn lambda_max(&mut self, l1_ratio: f64) -> f64 {
+        let length = total_weight(&self.weights, self.x.len());
+        let (columns, response, _, _) = centered_columns(self);
+
+        // Smallest lambda at which every coefficient is thresholded to zero.
+        // A pure ridge penalty never zeroes coefficients, so the ratio is
+        // floored the same way glmnet does.
+        let largest = columns
+            .iter()
+            .map(|column| (linalg::dot(column, &response) / length).abs())
+            .fold(0.0, f64::max);
+
+        2.0 * largest / l1_ratio.max(1e-3)
+    }
+}
+
+impl ElasticNet for LinearFrame {
+    fn coordinate_descent(
+        &mut self,
+        coefficients: &[f64],
+        lambda: f64,
+        l1_ratio: f64,
+        epoch: i32,
+        tolerance: f64,
+    ) -> (Vec<f64>, f64) {
+        MultiFrame::from(&*self).coordinate_descent(
+            coefficients,
+            lambda,
+            l1_ratio,
+            epoch,
+            tolerance,
+        )
+    }
+
+    fn lasso(&mut self, lambda: f64) -> (Vec<f64>, f64) {
+        MultiFrame::from(&*self).lasso(lambda)
+    }
+
+    fn elastic_net(&mut self, lambda: f64, l1_ratio: f64) -> (Vec<f64>, f64) {
+        MultiFrame::from(&*self).elastic_net(lambda, l1_ratio)
+    }
+
+    fn elastic_net_path(&mut self, lambdas: &[f64], l1_ratio: f64) -> Vec<PathStep> {
+        MultiFrame::from(&*self).elastic_net_path(lambdas, l1_ratio)
+    }
+
+    fn lambda_max(&mut self, l1_ratio: f64) -> f64 {
+        MultiFrame::from(&*self).lambda_max(l1_ratio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MultiRegression, Ridge};
+
+    // y = 3x1 - 2x2 + 1, the third feature is noise
+    fn sparse() -> MultiFrame {
+        let x = vec![
+            vec![1.0, 4.0, 0.3],
+            vec![2.0, 1.0, -0.2],
+            vec![3.0, 3.0, 0.5],
+            vec![4.0, 0.0, -0.4],
+            vec![5.0, 2.0, 0.1],
+            vec![6.0, 5.0, -0.3],
+            vec![7.0, 1.0, 0.2],
+            vec![8.0, 3.0, 0.0],
+        ];
+        let y = x.iter().map(|x| 3.0 * x[0] - 2.0 * x[1] + 1.0).collect();
+
+        MultiFrame {
+            x,
+            y,
+            weights: None,
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn soft_threshold_test() {
+        assert_eq!(soft_threshold(3.0, 1.0), 2.0);
+        assert_eq!(soft_threshold(-3.0, 1.0), -2.0);
+        assert_eq!(soft_threshold(0.5, 1.0), 0.0);
+    }
+
+    #[test]
+    fn lasso_test() {
+        let mut frame = sparse();
+
+        let (ols, ols_b) = frame.least_squares();
+        let (unpenalized, b) = frame.lasso(0.0);
+
+        for (a, b) in zip(&ols, &unpenalized) {
+            assert!(f64::abs(a - b) < 1e-8);
+        }
+        assert!(f64::abs(ols_b - b) < 1e-8);
+
+        let (coefficients, _) = frame.lasso(0.5);
+        assert_eq!(coefficients[2], 0.0);
+        assert!(coefficients[0] > 0.0);
+        assert!(coefficients[1] < 0.0);
+
+        let lambda_max = frame.lambda_max(1.0);
+        let (coefficients, _) = frame.lasso(lambda_max * 1.0001);
+        assert!(coefficients.iter().all(|c| *c == 0.0));
+    }
+
+    #[test]
+    fn elastic_net_matches_ridge() {
+        let mut frame = sparse();
+
+        let (ridge, ridge_b) = frame.ridge_least_squares(0.3);
+        let (net, net_b) = frame.elastic_net(0.3, 0.0);
+
+        for (a, b) in zip(&ridge, &net) {
+            assert!(f64::abs(a - b) < 1e-8);
+        }
+        assert!(f64::abs(ridge_b - net_b) < 1e-8);
+    }
+
+    #[test]
+    fn elastic_net_path_test() {
+        let mut frame = sparse();
+        let lambda_max = frame.lambda_max(0.5);
+        let lambdas: Vec<f64> = (0..10).map(|i| lambda_max * 0.5_f64.powi(i)).collect();
+
+        let path = frame.elastic_net_path(&lambdas, 0.5);
+
+        assert_eq!(path.len(), 10);
+        assert!(path[0].active.is_empty());
+        assert_eq!(path[9].active, vec![0, 1]);
+        for pair in path.windows(2) {
+            assert!(pair[0].lambda > pair[1].lambda);
+            assert!(pair[0].active.len() <= pair[1].active.len());
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegressionError {
+    /// A column does not have one value per response.
+    LengthMismatch { expected: usize, found: usize },
+    /// The frame has no samples.
+    Empty,
+    /// A NaN or infinite value in the sample at `index`.
+    NonFinite { index: usize },
+    /// A negative weight on the sample at `index`.
+    InvalidWeight { index: usize },
+    /// The features are collinear or constant, so the fit is not unique.
+    Singular,
+    /// The parameters stopped being finite during training.
+    Diverged { epoch: i32 },
+}
+
+impl fmt::Display for RegressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegressionError::LengthMismatch { expected, found } => {
+                write!(f, "expected {} values but found {}", expected, found)
+            }
+            RegressionError::Empty => write!(f, "the frame has no samples"),
+            RegressionError::NonFinite { index } => {
+                write!(f, "sample {} contains a NaN or infinite value", index)
+            }
+            RegressionError::InvalidWeight { index } => {
+                write!(f, "sample {} has a negative weight", index)
+            }
+            RegressionError::Singular => {
+                write!(
+                    f,
+                    "the design matrix is singular, features are collinear or constant"
+                )
+            }
+            RegressionError::Diverged { epoch } => {
+                write!(f, "gradient descent diverged at epoch {}", epoch)
+            }
+        }
+    }
+}
+
+impl Error for RegressionError {}
+
+/// Checks that `x` (given as its length and a finiteness test per sample),
+/// `y` and `weights` describe the same non-empty set of finite samples.
+pub(crate) fn validate(
+    samples: usize,
+    y: &[f64],
+    weights: &Option<Vec<f64>>,
+    finite: impl Fn(usize) -> bool,
+) -> Result<(), RegressionError> {
+    if samples != y.len() {
+        return Err(RegressionError::LengthMismatch {
+            expected: y.len(),
+            found: samples,
+        });
+    }
+    if let Some(weights) = weights {
+        if weights.len() != y.len() {
+            return Err(RegressionError::LengthMismatch {
+                expected: y.len(),
+                found: weights.len(),
+            });
+        }
+    }
+    if y.is_empty() {
+        return Err(RegressionError::Empty);
+    }
+
+    for (index, y) in y.iter().enumerate() {
+        let w = weights.as_ref().map_or(1.0, |weights| weights[index]);
+        if !y.is_finite() || !w.is_finite() || !finite(index) {
+            return Err(RegressionError::NonFinite { index });
+        }
+        if w < 0.0 {
+            return Err(RegressionError::InvalidWeight { index });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,14 @@
+//! Data shared by the unit tests of several modules.
+
+use crate::LinearFrame;
+
+/// Five samples close to `y = x`. The least squares line is `y = x + 0.2`
+/// with SSE = 0.8, Sxx = 10 and mean(x) = 3.
+pub(crate) fn frame() -> LinearFrame {
+    LinearFrame {
+        x: vec![1.0, 2.0, 3.0, 4.0, 5.0],
+        y: vec![1.0, 2.0, 4.0, 4.0, 5.0],
+        weights: None,
+        verbose: false,
+    }
+}
@@ -0,0 +1,460 @@
+use std::iter::zip;
+
+use crate::distributions::{normal_cdf, normal_pdf, normal_quantile};
+use crate::linalg;
+use crate::multi::{design_matrix, means, weight};
+use crate::trace::{epoch, fit_span};
+use crate::{LinearFrame, MultiFrame};
+
+const TOLERANCE: f64 = 1e-10;
+
+/// Maps the mean `mu` of the response to the linear predictor `eta`.
+pub trait Link {
+    fn link(&self, mu: f64) -> f64;
+    fn inverse(&self, eta: f64) -> f64;
+    /// Derivative of `link` with respect to `mu`.
+    fn derivative(&self, mu: f64) -> f64;
+}
+
+/// Distribution of the response around its mean.
+pub trait Family {
+    fn variance(&self, mu: f64) -> f64;
+    /// Contribution of one observation to the deviance.
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64;
+    fn default_link(&self) -> Box<dyn Link>;
+
+    /// Whether the dispersion is estimated from the data, or fixed at 1.
+    fn estimates_dispersion(&self) -> bool {
+        true
+    }
+
+    /// Keeps a mean inside the family's support while iterating.
+    fn clamp(&self, mu: f64) -> f64 {
+        mu.max(1e-10)
+    }
+
+    fn starting_mu(&self, y: f64, mean: f64) -> f64 {
+        (y + mean) / 2.0
+    }
+}
+
+pub struct Identity;
+pub struct Log;
+pub struct Logit;
+pub struct Probit;
+pub struct Inverse;
+
+impl Link for Identity {
+    fn link(&self, mu: f64) -> f64 {
+        mu
+    }
+
+    fn inverse(&self, eta: f64) -> f64 {
+        eta
+    }
+
+    fn derivative(&self, _mu: f64) -> f64 {
+        1.0
+    }
+}
+
+impl Link for Log {
+    fn link(&self, mu: f64) -> f64 {
+        mu.ln()
+    }
+
+    fn inverse(&self, eta: f64) -> f64 {
+        eta.exp()
+    }
+
+    fn derivative(&self, mu: f64) -> f64 {
+        1.0 / mu
+    }
+}
+
+impl Link for Logit {
+    fn link(&self, mu: f64) -> f64 {
+        (mu / (1.0 - mu)).ln()
+    }
+
+    fn inverse(&self, eta: f64) -> f64 {
+        1.0 / (1.0 + (-eta).exp())
+    }
+
+    fn derivative(&self, mu: f64) -> f64 {
+        1.0 / (mu * (1.0 - mu))
+    }
+}
+
+impl Link for Probit {
+    fn link(&self, mu: f64) -> f64 {
+        normal_quantile(mu)
+    }
+
+    fn inverse(&self, eta: f64) -> f64 {
+        normal_cdf(eta)
+    }
+
+    fn derivative(&self, mu: f64) -> f64 {
+        1.0 / normal_pdf(normal_quantile(mu))
+    }
+}
+
+impl Link for Inverse {
+    fn link(&self, mu: f64) -> f64 {
+        1.0 / mu
+    }
+
+    fn inverse(&self, eta: f64) -> f64 {
+        1.0 / eta
+    }
+
+    fn derivative(&self, mu: f64) -> f64 {
+        -1.0 / (mu * mu)
+    }
+}
+
+pub struct Gaussian;
+pub struct Binomial;
+pub struct Poisson;
+pub struct Gamma;
+pub struct InverseGaussian;
+
+/// `y · ln(y / mu)`, taking `0 · ln 0` as 0.
+fn y_log_y(y: f64, mu: f64) -> f64 {
+    if y == 0.0 {
+        0.0
+    } else {
+        y * (y / mu).ln()
+    }
+}
+
+impl Family for Gaussian {
+    fn variance(&self, _mu: f64) -> f64 {
+        1.0
+    }
+
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 {
+        (y - mu) * (y - mu)
+    }
+
+    fn default_link(&self) -> Box<dyn Link> {
+        Box::new(Identity)
+    }
+
+    fn clamp(&self, mu: f64) -> f64 {
+        mu
+    }
+
+    fn starting_mu(&self, y: f64, _mean: f64) -> f64 {
+        y
+    }
+}
+
+impl Family for Binomial {
+    fn variance(&self, mu: f64) -> f64 {
+        mu * (1.0 - mu)
+    }
+
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 {
+        2.0 * (y_log_y(y, mu) + y_log_y(1.0 - y, 1.0 - mu))
+    }
+
+    fn default_link(&self) -> Box<dyn Link> {
+        Box::new(Logit)
+    }
+
+    fn estimates_dispersion(&self) -> bool {
+        false
+    }
+
+    fn clamp(&self, mu: f64) -> f64 {
+        mu.clamp(1e-10, 1.0 - 1e-10)
+    }
+
+    fn starting_mu(&self, y: f64, _mean: f64) -> f64 {
+        (y + 0.5) / 2.0
+    }
+}
+
+impl Family for Poisson {
+    fn variance(&self, mu: f64) -> f64 {
+        mu
+    }
+
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 {
+        2.0 * (y_log_y(y, mu) - (y - mu))
+    }
+
+    fn default_link(&self) -> Box<dyn Link> {
+        Box::new(Log)
+    }
+
+    fn estimates_dispersion(&self) -> bool {
+        false
+    }
+}
+
+impl Family for Gamma {
+    fn variance(&self, mu: f64) -> f64 {
+        mu * mu
+    }
+
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 {
+        2.0 * (-(y / mu).ln() + (y - mu) / mu)
+    }
+
+    fn default_link(&self) -> Box<dyn Link> {
+        Box::new(Inverse)
+    }
+}
+
+impl Family for InverseGaussian {
+    fn variance(&self, mu: f64) -> f64 {
+        mu * mu * mu
+    }
+
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 {
+        (y - mu) * (y - mu) / (y * mu * mu)
+    }
+
+    fn default_link(&self) -> Box<dyn Link> {
+        Box::new(Inverse)
+    }
+}
+
+/// Result of fitting a generalized linear model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlmFit {
+    pub coefficients: Vec<f64>,
+    pub b: f64,
+    pub deviance: f64,
+    /// Deviance of the intercept-only model.
+    pub null_deviance: f64,
+    /// Pearson estimate of the dispersion, or 1 for Binomial and Poisson.
+    pub dispersion: f64,
+    pub iterations: i32,
+    pub converged: bool,
+}
+
+impl GlmFit {
+    pub fn linear_predictor(&self, x: &[f64]) -> f64 {
+        linalg::dot(&self.coefficients, x) + self.b
+    }
+
+    /// Predicted mean response for one sample.
+    pub fn predict(&self, x: &[f64], link: &dyn Link) -> f64 {
+        link.inverse(self.linear_predictor(x))
+    }
+}
+
+pub trait GeneralizedLinear {
+    fn glm(&mut self, family: &dyn Family, link: &dyn Link, epoch: i32) -> GlmFit;
+}
+
+impl GeneralizedLinear for MultiFrame {
+    fn glm(&mut self, family: &dyn Family, link: &dyn Link, epoch: i32) -> GlmFit {
+        fit_span!(
+            "glm",
+            samples = self.x.len(),
+            features = self.features(),
+            epoch
+        );
+        let design = design_matrix(&self.x);
+        let length = self.y.len() as f64;
+        let (_, y_mean) = means(&self.x, &self.y, &self.weights);
+
+        // Sample weights act as prior weights on every observation
+        let deviance = |mu: &[f64]| -> f64 {
+            zip(&self.y, mu)
+                .enumerate()
+                .map(|(i, (y, mu))| weight(&self.weights, i) * family.unit_deviance(*y, *mu))
+                .sum()
+        };
+
+        let mut mu: Vec<f64> = self
+            .y
+            .iter()
+            .map(|y| family.clamp(family.starting_mu(*y, y_mean)))
+            .collect();
+        let mut eta: Vec<f64> = mu.iter().map(|mu| link.link(*mu)).collect();
+        let mut parameters = vec![0.0; self.features() + 1];
+        let mut current = deviance(&mu);
+        let mut iterations = 0;
+        let mut converged = false;
+
+        // Iteratively reweighted least squares on the working response
+        while iterations < epoch {
+            iterations += 1;
+
+            let mut rows = Vec::with_capacity(design.len());
+            let mut response = Vec::with_capacity(design.len());
+
+            for (i, row) in design.iter().enumerate() {
+                let derivative = link.derivative(mu[i]);
+                let root = (weight(&self.weights, i)
+                    / (family.variance(mu[i]) * derivative * derivative))
+                    .sqrt();
+
+                rows.push(row.iter().map(|x| x * root).collect());
+                response.push((eta[i] + (self.y[i] - mu[i]) * derivative) * root);
+            }
+
+            parameters = match linalg::least_squares(&rows, &response) {
+                Some(parameters) => parameters,
+                None => vec![f64::NAN; parameters.len()],
+            };
+
+            eta = design
+                .iter()
+                .map(|row| linalg::dot(&parameters, row))
+                .collect();
+            mu = eta
+                .iter()
+                .map(|eta| family.clamp(link.inverse(*eta)))
+                .collect();
+
+            let next = deviance(&mu);
+
+            epoch!(iteration = iterations, deviance = next);
+
+            let change = (next - current).abs() / (next.abs() + 0.1);
+            current = next;
+
+            if change < TOLERANCE {
+                converged = true;
+                break;
+            }
+        }
+
+        // The intercept-only maximum likelihood mean is the sample mean
+        let null_mu = vec![family.clamp(y_mean); self.y.len()];
+
+        let dispersion = if family.estimates_dispersion() {
+            let pearson: f64 = zip(&self.y, &mu)
+                .enumerate()
+                .map(|(i, (y, mu))| {
+                    weight(&self.weights, i) * (y - mu) * (y - mu) / family.variance(*mu)
+                })
+                .sum();
+            pearson / (length - parameters.len() as f64)
+        } else {
+            1.0
+        };
+
+        GlmFit {
+            coefficients: parameters[1..].to_vec(),
+            b: parameters[0],
+            deviance: current,
+            null_deviance: deviance(&null_mu),
+            dispersion,
+            iterations,
+            converged,
+        }
+    }
+}
+
+impl GeneralizedLinear for LinearFrame {
+    fn glm(&mut self, family: &dyn Family, link: &dyn Link, epoch: i32) -> GlmFit {
+        MultiFrame::from(&*self).glm(family, link, epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LogisticFrame, LogisticRegression, MultiRegression};
+
+    fn frame(x: Vec<f64>, y: Vec<f64>) -> MultiFrame {
+        MultiFrame {
+            x: x.into_iter().map(|x| vec![x]).collect(),
+            y,
+            weights: None,
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn gaussian_matches_least_squares() {
+        let mut frame = frame(
+            vec![3.0, 2.0, 1.0, 4.3, 3.4, 8.2, 1.1, 4.5, 6.7],
+            vec![13.2, 9.9, 7.0, 16.8, 14.2, 28.7, 7.3, 17.5, 24.0],
+        );
+
+        let fit = frame.glm(&Gaussian, &Identity, 25);
+        let (coefficients, b) = frame.least_squares();
+        let sse = frame.squared_error(&|x| coefficients[0] * x[0] + b);
+
+        assert!(fit.converged);
+        assert!(f64::abs(fit.coefficients[0] - coefficients[0]) < 1e-10);
+        assert!(f64::abs(fit.b - b) < 1e-10);
+        assert!(f64::abs(fit.deviance - sse) < 1e-10);
+        assert!(f64::abs(fit.dispersion - sse / 7.0) < 1e-10);
+    }
+
+    #[test]
+    fn binomial_matches_logistic() {
+        let x = vec![0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 4.5, 5.0];
+        let y = vec![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0];
+
+        let fit = frame(x.clone(), y.clone()).glm(&Binomial, &*Binomial.default_link(), 50);
+        let model = LogisticFrame {
+            x: x.into_iter().map(|x| vec![x]).collect(),
+            y,
+            verbose: false,
+        }
+        .newton(50);
+
+        assert!(fit.converged);
+        assert_eq!(fit.dispersion, 1.0);
+        assert!(f64::abs(fit.coefficients[0] - model.coefficients[0]) < 1e-8);
+        assert!(f64::abs(fit.b - model.b) < 1e-8);
+        assert!(f64::abs(fit.null_deviance - 20.0 * 2.0_f64.ln()) < 1e-10);
+        assert!(fit.deviance < fit.null_deviance);
+    }
+
+    #[test]
+    fn poisson_log_link() {
+        // Exact counts from mu = exp(0.5 + 0.3x) give a perfect fit
+        let x: Vec<f64> = (0..10).map(|x| x as f64).collect();
+        let y: Vec<f64> = x.iter().map(|x| (0.5 + 0.3 * x).exp()).collect();
+
+        let fit = frame(x, y).glm(&Poisson, &Log, 50);
+
+        assert!(fit.converged);
+        assert!(f64::abs(fit.coefficients[0] - 0.3) < 1e-8);
+        assert!(f64::abs(fit.b - 0.5) < 1e-8);
+        assert!(fit.deviance < 1e-10);
+        assert!(f64::abs(fit.predict(&[2.0], &Log) - 1.1_f64.exp()) < 1e-8);
+    }
+
+    #[test]
+    fn links_invert() {
+        let links: [&dyn Link; 5] = [&Identity, &Log, &Logit, &Probit, &Inverse];
+
+        for link in links {
+            let mu = 0.3;
+            assert!(f64::abs(link.inverse(link.link(mu)) - mu) < 1e-12);
+
+            // Compare against a central difference
+            let h = 1e-6;
+            let numeric = (link.link(mu + h) - link.link(mu - h)) / (2.0 * h);
+            assert!(f64::abs(link.derivative(mu) - numeric) < 1e-5);
+        }
+    }
+
+    #[test]
+    fn gamma_and_inverse_gaussian_fit() {
+        let x: Vec<f64> = (1..=8).map(|x| x as f64).collect();
+        let y = vec![2.1, 1.3, 0.9, 0.75, 0.55, 0.5, 0.42, 0.37];
+
+        let gamma = frame(x.clone(), y.clone()).glm(&Gamma, &Inverse, 50);
+        let inverse_gaussian = frame(x, y).glm(&InverseGaussian, &Log, 50);
+
+        assert!(gamma.converged && inverse_gaussian.converged);
+        assert!(gamma.coefficients[0] > 0.0);
+        assert!(inverse_gaussian.coefficients[0] < 0.0);
+        assert!(gamma.deviance < gamma.null_deviance);
+        assert!(inverse_gaussian.deviance < inverse_gaussian.null_deviance);
+    }
+}
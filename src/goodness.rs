@@ -0,0 +1,144 @@
+use std::f64::consts::PI;
+use std::iter::zip;
+
+use crate::distributions::f_p_value;
+use crate::multi::{total_weight, weight};
+
+/// Summary statistics of how well a fitted model explains its data.
+///
+/// The likelihood based criteria count the intercept and coefficients as
+/// parameters but not the error variance, matching statsmodels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoodnessOfFit {
+    pub r_squared: f64,
+    pub adjusted_r_squared: f64,
+    /// F-statistic for the hypothesis that every coefficient is zero.
+    pub f_statistic: f64,
+    pub f_p_value: f64,
+    /// Gaussian log-likelihood at the maximum likelihood error variance.
+    pub log_likelihood: f64,
+    pub aic: f64,
+    pub bic: f64,
+}
+
+impl GoodnessOfFit {
+    /// Computes the statistics for a model with `features` coefficients plus
+    /// an intercept that predicted `fitted` for the responses `y`.
+    pub fn new(y: &[f64], fitted: &[f64], features: usize) -> Self {
+        GoodnessOfFit::weighted(y, fitted, &None, features)
+    }
+
+    /// Same as `new` for a weighted least squares fit. Samples with zero
+    /// weight do not count as observations.
+    pub fn weighted(
+        y: &[f64],
+        fitted: &[f64],
+        weights: &Option<Vec<f64>>,
+        features: usize,
+    ) -> Self {
+        let length = (0..y.len()).filter(|i| weight(weights, *i) > 0.0).count() as f64;
+        let parameters = features as f64 + 1.0;
+        let total_weight = total_weight(weights, y.len());
+        let y_mean = (0..y.len()).map(|i| weight(weights, i) * y[i]).sum::<f64>() / total_weight;
+
+        let mut total = 0.0;
+        let mut residual = 0.0;
+        let mut log_weights = 0.0;
+        for (i, (y, f)) in zip(y, fitted).enumerate() {
+            let w = weight(weights, i);
+            if w == 0.0 {
+                continue;
+            }
+            total += w * (y - y_mean) * (y - y_mean);
+            residual += w * (y - f) * (y - f);
+            log_weights += w.ln();
+        }
+
+        let r_squared = 1.0 - residual / total;
+        let adjusted_r_squared = 1.0 - (1.0 - r_squared) * (length - 1.0) / (length - parameters);
+
+        let f_statistic =
+            ((total - residual) / features as f64) / (residual / (length - parameters));
+
+        let log_likelihood =
+            -length / 2.0 * ((2.0 * PI).ln() + (residual / length).ln() + 1.0) + log_weights / 2.0;
+
+        GoodnessOfFit {
+            r_squared,
+            adjusted_r_squared,
+            f_statistic,
+            f_p_value: f_p_value(f_statistic, features as f64, length - parameters),
+            log_likelihood,
+            aic: -2.0 * log_likelihood + 2.0 * parameters,
+            bic: -2.0 * log_likelihood + parameters * length.ln(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame;
+    use crate::{Inference, MultiFrame, MultiRegression, Regression};
+
+    #[test]
+    fn goodness_of_fit_test() {
+        let mut frame = frame();
+        let (slope, b) = frame.least_squares();
+
+        let fit = frame.goodness_of_fit(&|x| slope * x + b);
+
+        // SST = 10.8, SSE = 0.8
+        assert!(f64::abs(fit.r_squared - 10.0 / 10.8) < 1e-12);
+        assert!(f64::abs(fit.adjusted_r_squared - (1.0 - (0.8 / 10.8) * 4.0 / 3.0)) < 1e-12);
+        assert!(f64::abs(fit.f_statistic - 37.5) < 1e-10);
+        // With one coefficient F equals the squared t statistic
+        let t = &frame.inference().coefficients[0];
+        assert!(f64::abs(fit.f_p_value - t.p_value) < 1e-12);
+
+        let log_likelihood = -2.5 * ((2.0 * PI).ln() + (0.16_f64).ln() + 1.0);
+        assert!(f64::abs(fit.log_likelihood - log_likelihood) < 1e-12);
+        assert!(f64::abs(fit.aic - (-2.0 * log_likelihood + 4.0)) < 1e-12);
+        assert!(f64::abs(fit.bic - (-2.0 * log_likelihood + 2.0 * 5.0_f64.ln())) < 1e-12);
+
+        assert_eq!(frame.r_squared(&|x| slope * x + b), fit.r_squared);
+    }
+
+    #[test]
+    fn multi_goodness_of_fit_matches_linear() {
+        let mut linear = frame();
+        let mut multi = MultiFrame::from(&linear);
+        let (slope, b) = linear.least_squares();
+
+        let expected = linear.goodness_of_fit(&|x| slope * x + b);
+
+        assert_eq!(multi.goodness_of_fit(&|x| slope * x[0] + b), expected);
+        let inferred = multi.inference().goodness_of_fit();
+        assert!(f64::abs(inferred.r_squared - expected.r_squared) < 1e-12);
+        assert!(f64::abs(inferred.aic - expected.aic) < 1e-10);
+    }
+
+    #[test]
+    fn zero_weight_test() {
+        let mut weighted = frame();
+        weighted.weights = Some(vec![1.0, 0.0, 1.0, 1.0, 1.0]);
+        let mut dropped = frame();
+        dropped.x.remove(1);
+        dropped.y.remove(1);
+
+        // A sample without weight is the same as no sample at all
+        let expected = dropped.inference().goodness_of_fit();
+        let fit = weighted.inference().goodness_of_fit();
+        for (value, expected) in [
+            (fit.r_squared, expected.r_squared),
+            (fit.adjusted_r_squared, expected.adjusted_r_squared),
+            (fit.f_statistic, expected.f_statistic),
+            (fit.f_p_value, expected.f_p_value),
+            (fit.log_likelihood, expected.log_likelihood),
+            (fit.aic, expected.aic),
+            (fit.bic, expected.bic),
+        ] {
+            assert!(f64::abs(value - expected) < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::iter::zip;
+
+use crate::distributions::{student_t_p_value, student_t_quantile};
+use crate::linalg;
+use crate::multi::{design_matrix, weight, whiten};
+use crate::{Covariance, GoodnessOfFit, LinearFrame, MultiFrame, MultiRegression, Summary};
+
+/// An estimated parameter with its sampling uncertainty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coefficient {
+    pub estimate: f64,
+    pub standard_error: f64,
+    pub t_statistic: f64,
+    /// Two-sided p-value for the hypothesis that the parameter is zero.
+    pub p_value: f64,
+    pub degrees_of_freedom: f64,
+}
+
+impl Coefficient {
+    pub fn new(estimate: f64, standard_error: f64, degrees_of_freedom: f64) -> Self {
+        let t_statistic = estimate / standard_error;
+
+        Coefficient {
+            estimate,
+            standard_error,
+            t_statistic,
+            p_value: student_t_p_value(t_statistic, degrees_of_freedom),
+            degrees_of_freedom,
+        }
+    }
+
+    /// Two-sided confidence interval, e.g. `level = 0.95`.
+    pub fn confidence_interval(&self, level: f64) -> (f64, f64) {
+        let t = student_t_quantile(0.5 + level / 2.0, self.degrees_of_freedom);
+        let margin = t * self.standard_error;
+
+        (self.estimate - margin, self.estimate + margin)
+    }
+}
+
+pub(crate) fn coefficient_table(
+    parameters: &[f64],
+    covariance: &[Vec<f64>],
+    degrees_of_freedom: f64,
+) -> (Coefficient, Vec<Coefficient>) {
+    let mut table: Vec<Coefficient> = parameters
+        .iter()
+        .enumerate()
+        .map(|(i, estimate)| {
+            Coefficient::new(*estimate, covariance[i][i].sqrt(), degrees_of_freedom)
+        })
+        .collect();
+    let coefficients = table.split_off(1);
+
+    (table.remove(0), coefficients)
+}
+
+/// Prediction for a new sample with its uncertainty.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Prediction {
+    pub mean: f64,
+    /// Interval for the mean response at the sample.
+    pub confidence: (f64, f64),
+    /// Interval for a single new observation at the sample.
+    pub prediction: (f64, f64),
+}
+
+/// A linear fit together with the classical least squares inference for its
+/// parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearFit {
+    pub intercept: Coefficient,
+    pub coefficients: Vec<Coefficient>,
+    /// Unbiased estimate of the error variance, `SSE / (n - p - 1)`, or NaN
+    /// when there are no residual degrees of freedom.
+    pub residual_variance: f64,
+    pub degrees_of_freedom: f64,
+    /// Covariance of the estimates, ordered intercept first.
+    pub covariance: Vec<Vec<f64>>,
+    /// Estimator used for `covariance` and the standard errors.
+    pub covariance_type: Covariance,
+    /// Residuals and fitted values; for weighted fits both are scaled by the
+    /// square root of the sample weights.
+    pub residuals: Vec<f64>,
+    pub fitted: Vec<f64>,
+    pub weights: Option<Vec<f64>>,
+    pub(crate) design: Vec<Vec<f64>>,
+    pub(crate) y: Vec<f64>,
+    /// `(XᵀX)⁻¹` of the design including the intercept column.
+    pub(crate) gram_inverse: Vec<Vec<f64>>,
+}
+
+impl LinearFit {
+    /// Computes the inference for the given parameters of a model fitted to
+    /// the samples `x` and responses `y`.
+    pub fn new(x: &[Vec<f64>], y: &[f64], coefficients: &[f64], b: f64) -> Self {
+        LinearFit::weighted(x, y, &None, coefficients, b)
+    }
+
+    /// Same as `new` for a weighted least squares fit.
+    pub fn weighted(
+        x: &[Vec<f64>],
+        y: &[f64],
+        weights: &Option<Vec<f64>>,
+        coefficients: &[f64],
+        b: f64,
+    ) -> Self {
+        let (design, y) = whiten(&design_matrix(x), y, weights);
+        let parameters: Vec<f64> = std::iter::once(b)
+            .chain(coefficients.iter().copied())
+            .collect();
+
+        let fitted = linalg::multiply(&design, &parameters);
+        let residuals: Vec<f64> = zip(&y, &fitted).map(|(y, f)| y - f).collect();
+
+        let degrees_of_freedom = y.len() as f64 - parameters.len() as f64;
+        // Without residual degrees of freedom the variance is not estimable
+        let residual_variance = if degrees_of_freedom > 0.0 {
+            linalg::dot(&residuals, &residuals) / degrees_of_freedom
+        } else {
+            f64::NAN
+        };
+
+        // An empty design inverts to an empty matrix, which is no more use
+        // than a singular one
+        let gram_inverse = linalg::inverse(&linalg::gram(&design))
+            .filter(|inverse| inverse.len() == parameters.len())
+            .unwrap_or_else(|| vec![vec![f64::NAN; parameters.len()]; parameters.len()]);
+        let covariance: Vec<Vec<f64>> = gram_inverse
+            .iter()
+            .map(|row| row.iter().map(|v| v * residual_variance).collect())
+            .collect();
+
+        let (intercept, coefficients) =
+            coefficient_table(&parameters, &covariance, degrees_of_freedom);
+
+        LinearFit {
+            intercept,
+            coefficients,
+            residual_variance,
+            degrees_of_freedom,
+            covariance,
+            covariance_type: Covariance::Classic,
+            residuals,
+            fitted,
+            weights: weights.clone(),
+            design,
+            y,
+            gram_inverse,
+        }
+    }
+
+    pub fn summary(&self) -> Summary {
+        Summary::new(self)
+    }
+
+    pub fn goodness_of_fit(&self) -> GoodnessOfFit {
+        // Undo the whitening so the statistics see the original responses.
+        // Whitening zeroed the samples without weight, they are left out.
+        let kept: Vec<usize> = (0..self.y.len())
+            .filter(|i| weight(&self.weights, *i) > 0.0)
+            .collect();
+        let unscale = |values: &[f64]| -> Vec<f64> {
+            kept.iter()
+                .map(|&i| values[i] / weight(&self.weights, i).sqrt())
+                .collect()
+        };
+        let weights = self
+            .weights
+            .as_ref()
+            .map(|weights| kept.iter().map(|&i| weights[i]).collect());
+
+        GoodnessOfFit::weighted(
+            &unscale(&self.y),
+            &unscale(&self.fitted),
+            &weights,
+            self.coefficients.len(),
+        )
+    }
+
+    /// Leverage of a (possibly new) sample, `x₀ᵀ (XᵀX)⁻¹ x₀` with the
+    /// intercept term prepended to `x`.
+    pub fn leverage(&self, x: &[f64]) -> f64 {
+        let row: Vec<f64> = std::iter::once(1.0).chain(x.iter().copied()).collect();
+        linalg::dot(&row, &linalg::multiply(&self.gram_inverse, &row))
+    }
+
+    /// Predicts the response at `x` with intervals at `level`.
+    pub fn predict(&self, x: &[f64], level: f64) -> Prediction {
+        let mean = zip(x, &self.coefficients)
+            .map(|(x, c)| x * c.estimate)
+            .sum::<f64>()
+            + self.intercept.estimate;
+
+        let leverage = self.leverage(x);
+        let t = student_t_quantile(0.5 + level / 2.0, self.degrees_of_freedom);
+
+        let mean_error = (self.residual_variance * leverage).sqrt();
+        let observation_error = (self.residual_variance * (1.0 + leverage)).sqrt();
+
+        Prediction {
+            mean,
+            confidence: (mean - t * mean_error, mean + t * mean_error),
+            prediction: (mean - t * observation_error, mean + t * observation_error),
+        }
+    }
+
+    /// Confidence intervals at `level`, ordered intercept first.
+    pub fn confidence_intervals(&self, level: f64) -> Vec<(f64, f64)> {
+        std::iter::once(&self.intercept)
+            .chain(&self.coefficients)
+            .map(|c| c.confidence_interval(level))
+            .collect()
+    }
+}
+
+pub trait Inference {
+    fn inference(&mut self) -> LinearFit;
+}
+
+impl Inference for MultiFrame {
+    fn inference(&mut self) -> LinearFit {
+        let (coefficients, b) = self.least_squares();
+        LinearFit::weighted(&self.x, &self.y, &self.weights, &coefficients, b)
+    }
+}
+
+impl Inference for LinearFrame {
+    fn inference(&mut self) -> LinearFit {
+        MultiFrame::from(&*self).inference()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame;
+
+    #[test]
+    fn predict_test() {
+        let mut frame = frame();
+
+        let fit = frame.inference();
+        let prediction = fit.predict(&[6.0], 0.95);
+
+        // h = 1/n + (x - mean(x))² / Sxx
+        let leverage = 1.0 / 5.0 + 9.0 / 10.0;
+        assert!(f64::abs(fit.leverage(&[6.0]) - leverage) < 1e-12);
+        assert!(f64::abs(prediction.mean - 6.2) < 1e-12);
+
+        let t = 3.182_446_305_284_263;
+        let variance = 0.8 / 3.0;
+        let (low, high) = prediction.confidence;
+        assert!(f64::abs(high - low - 2.0 * t * f64::sqrt(variance * leverage)) < 1e-9);
+        let (low, high) = prediction.prediction;
+        assert!(f64::abs(high - low - 2.0 * t * f64::sqrt(variance * (1.0 + leverage))) < 1e-9);
+        assert!(f64::abs((low + high) / 2.0 - 6.2) < 1e-12);
+
+        // The mean response is most certain at the center of the data
+        let center = fit.predict(&[3.0], 0.95);
+        assert!(center.confidence.1 - center.confidence.0 < high - low);
+    }
+
+    #[test]
+    fn inference_test() {
+        let mut frame = frame();
+
+        let fit = frame.inference();
+
+        // SSE = 0.8 on 3 degrees of freedom, Sxx = 10 and mean(x) = 3
+        let variance = 0.8 / 3.0;
+        assert!(f64::abs(fit.coefficients[0].estimate - 1.0) < 1e-12);
+        assert!(f64::abs(fit.intercept.estimate - 0.2) < 1e-12);
+        assert!(f64::abs(fit.residual_variance - variance) < 1e-12);
+        assert_eq!(fit.degrees_of_freedom, 3.0);
+
+        let slope = &fit.coefficients[0];
+        let standard_error = f64::sqrt(variance / 10.0);
+        assert!(f64::abs(slope.standard_error - standard_error) < 1e-12);
+        assert!(f64::abs(slope.t_statistic - 1.0 / standard_error) < 1e-10);
+        assert!(f64::abs(slope.p_value - 0.008_754_412_359_024) < 1e-10);
+
+        let intercept = &fit.intercept;
+        let standard_error = f64::sqrt(variance * (1.0 / 5.0 + 9.0 / 10.0));
+        assert!(f64::abs(intercept.standard_error - standard_error) < 1e-12);
+
+        // t(0.975, 3) = 3.182446305284263
+        let (low, high) = slope.confidence_interval(0.95);
+        let margin = 3.182_446_305_284_263 * slope.standard_error;
+        assert!(f64::abs(low - (1.0 - margin)) < 1e-9);
+        assert!(f64::abs(high - (1.0 + margin)) < 1e-9);
+
+        let intervals = fit.confidence_intervals(0.95);
+        assert_eq!(intervals.len(), 2);
+        assert_eq!(intervals[1], (low, high));
+    }
+
+    #[test]
+    fn underdetermined_test() {
+        // Three parameters from two samples
+        let mut frame =
+            MultiFrame::new(vec![vec![1.0, 2.0], vec![2.0, 1.0]], vec![1.0, 2.0]).unwrap();
+
+        let fit = frame.inference();
+
+        assert_eq!(fit.degrees_of_freedom, -1.0);
+        assert!(fit.residual_variance.is_nan());
+        assert!(fit.coefficients[0].standard_error.is_nan());
+        assert!(fit
+            .with_covariance(&Covariance::HC1)
+            .unwrap()
+            .intercept
+            .standard_error
+            .is_nan());
+
+        let mut empty = MultiFrame {
+            x: vec![],
+            y: vec![],
+            weights: None,
+            verbose: false,
+        };
+        let fit = empty.inference();
+        assert!(fit.intercept.estimate.is_nan());
+        assert!(fit.intercept.standard_error.is_nan());
+    }
+}
@@ -1,72 +1,290 @@
 use std::iter::zip;
 
-trait Regression {
+pub mod assumptions;
+mod callback;
+pub mod csv;
+mod diagnostics;
+pub mod distributions;
+mod elastic_net;
+mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod glm;
+mod goodness;
+mod inference;
+mod linalg;
+mod logistic;
+pub mod metrics;
+mod model;
+mod multi;
+pub mod optimizer;
+mod penalty;
+pub mod polynomial;
+mod ridge;
+mod rng;
+mod robust;
+mod schedule;
+mod summary;
+mod trace;
+mod training;
+
+pub use callback::{Callback, Control, EpochRecord, History, Logger};
+pub use diagnostics::{Influence, InfluenceFlags};
+pub use elastic_net::ElasticNet;
+pub use error::RegressionError;
+pub use glm::{Family, GeneralizedLinear, GlmFit, Link};
+pub use goodness::GoodnessOfFit;
+pub use inference::{Coefficient, Inference, LinearFit, Prediction};
+pub use logistic::{sigmoid, LogisticFrame, LogisticModel, LogisticRegression};
+#[cfg(feature = "serde")]
+pub use model::ModelError;
+pub use model::{FitMetadata, FittedModel, Transform, FORMAT_VERSION};
+use multi::{total_weight, weight};
+pub use multi::{MultiFrame, MultiRegression};
+pub use optimizer::Optimizer;
+use optimizer::Sgd;
+pub use penalty::{PathStep, Penalty};
+pub use polynomial::PolynomialRegression;
+pub use ridge::Ridge;
+pub use robust::Covariance;
+pub use schedule::{LineSearch, Schedule};
+pub use summary::Summary;
+use trace::{epoch, finished, fit_span};
+pub use training::{Batch, StopReason, Stopping, Train, Trainer, TrainingResult};
+
+pub trait Regression {
     fn squared_error(&mut self, f: &dyn Fn(f64) -> f64) -> f64;
     fn mean_squared_error(&mut self, f: &dyn Fn(f64) -> f64) -> f64;
     fn gradient_descent(&mut self, slope: f64, b: f64, learning_rate: f64) -> (f64, f64);
     fn regression(&mut self, epoch: i32, learning_rate: f64) -> (f64, f64);
+    fn least_squares(&mut self) -> (f64, f64);
+    fn fit(&mut self, solver: Solver) -> (f64, f64);
+    fn r_squared(&mut self, f: &dyn Fn(f64) -> f64) -> f64;
+    fn goodness_of_fit(&mut self, f: &dyn Fn(f64) -> f64) -> GoodnessOfFit;
+    fn try_regression(
+        &mut self,
+        epoch: i32,
+        learning_rate: f64,
+    ) -> Result<(f64, f64), RegressionError>;
+    fn try_least_squares(&mut self) -> Result<(f64, f64), RegressionError>;
+    fn try_fit(&mut self, solver: Solver) -> Result<(f64, f64), RegressionError>;
+}
+
+/// How `fit` finds the slope and intercept.
+///
+/// The closed-form solution is exact and only needs a single pass over the
+/// data, so it is the default for in-memory frames.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Solver {
+    #[default]
+    LeastSquares,
+    GradientDescent {
+        epoch: i32,
+        learning_rate: f64,
+    },
+}
+
+pub struct LinearFrame {
+    pub y: Vec<f64>,
+    pub x: Vec<f64>,
+    /// Optional per-sample weights, e.g. inverse measurement variances.
+    /// Every sample counts once when `None`.
+    pub weights: Option<Vec<f64>>,
+    pub verbose: bool,
 }
 
-struct LinearFrame {
-    y: Vec<f64>,
-    x: Vec<f64>,
-    verbose: bool,
+impl LinearFrame {
+    /// Builds a frame, checking that `x` and `y` have the same, non-zero
+    /// length and only finite values.
+    pub fn new(x: Vec<f64>, y: Vec<f64>) -> Result<Self, RegressionError> {
+        let frame = LinearFrame {
+            y,
+            x,
+            weights: None,
+            verbose: false,
+        };
+        frame.validate()?;
+        Ok(frame)
+    }
+
+    /// Same as `new` with per-sample weights, which must be non-negative.
+    pub fn weighted(x: Vec<f64>, y: Vec<f64>, weights: Vec<f64>) -> Result<Self, RegressionError> {
+        let frame = LinearFrame {
+            y,
+            x,
+            weights: Some(weights),
+            verbose: false,
+        };
+        frame.validate()?;
+        Ok(frame)
+    }
+
+    pub fn validate(&self) -> Result<(), RegressionError> {
+        error::validate(self.x.len(), &self.y, &self.weights, |i| {
+            self.x[i].is_finite()
+        })
+    }
+
+    /// Summary table for the line `y = slope · x + b` on this frame.
+    pub fn summary(&self, slope: f64, b: f64) -> Summary {
+        MultiFrame::from(self).summary(&[slope], b)
+    }
 }
 
 impl Regression for LinearFrame {
     fn squared_error(&mut self, f: &dyn Fn(f64) -> f64) -> f64 {
         let mut error = 0.0;
 
-        for (x, y) in zip(&self.x, &self.y) {
+        for (i, (x, y)) in zip(&self.x, &self.y).enumerate() {
             let delta = y - f(*x);
-            error += delta * delta;
+            error += weight(&self.weights, i) * delta * delta;
         }
 
         error
     }
 
     fn mean_squared_error(&mut self, f: &dyn Fn(f64) -> f64) -> f64 {
-        self.squared_error(f) / self.x.len() as f64
+        self.squared_error(f) / total_weight(&self.weights, self.x.len())
     }
 
     fn gradient_descent(&mut self, slope: f64, b: f64, learning_rate: f64) -> (f64, f64) {
-        let length = self.x.len() as f64;
+        let length = total_weight(&self.weights, self.x.len());
 
         let mut slope_gradient = 0.0;
         let mut b_gradient = 0.0;
 
-        for (x, y) in zip(&self.x, &self.y) {
+        for (i, (x, y)) in zip(&self.x, &self.y).enumerate() {
+            let w = weight(&self.weights, i);
             // Partial derivative with respect to slope
-            slope_gradient += -(2.0 / length) * x * (y - (slope * x + b));
+            slope_gradient += -(2.0 / length) * w * x * (y - (slope * x + b));
             // Partial derivative with respect to b
-            b_gradient += -(2.0 / length) * (y - (slope * x + b));
+            b_gradient += -(2.0 / length) * w * (y - (slope * x + b));
         }
 
-        (
-            slope - slope_gradient * learning_rate,
-            b - b_gradient * learning_rate,
-        )
+        let mut parameters = [b, slope];
+        Sgd.step(
+            &mut parameters,
+            &[b_gradient, slope_gradient],
+            learning_rate,
+        );
+
+        (parameters[1], parameters[0])
     }
 
     fn regression(&mut self, epoch: i32, learning_rate: f64) -> (f64, f64) {
+        fit_span!("regression", samples = self.x.len(), epoch, learning_rate);
+
         let mut slope = 0.0;
         let mut b = 0.0;
 
-        if self.verbose {
-            for x in 0..epoch {
-                (slope, b) = self.gradient_descent(slope, b, learning_rate);
+        for x in 0..epoch {
+            (slope, b) = self.gradient_descent(slope, b, learning_rate);
 
-                println!("Epoch: {}", x);
-                println!("y = {}x + {}", slope, b);
-            }
-        } else {
-            for _ in 0..epoch {
-                (slope, b) = self.gradient_descent(slope, b, learning_rate);
-            }
+            epoch!(epoch = x, slope, b);
+        }
+
+        finished!(self.verbose, self.summary(slope, b));
+
+        (slope, b)
+    }
+
+    fn least_squares(&mut self) -> (f64, f64) {
+        fit_span!("least_squares", samples = self.x.len());
+
+        let length = total_weight(&self.weights, self.x.len());
+
+        let mut x_mean = 0.0;
+        let mut y_mean = 0.0;
+        for (i, (x, y)) in zip(&self.x, &self.y).enumerate() {
+            x_mean += weight(&self.weights, i) * x / length;
+            y_mean += weight(&self.weights, i) * y / length;
+        }
+
+        // Working with centered sums avoids the cancellation of the raw
+        // normal equations when x is far from zero
+        let mut sxx = 0.0;
+        let mut sxy = 0.0;
+
+        for (i, (x, y)) in zip(&self.x, &self.y).enumerate() {
+            let w = weight(&self.weights, i);
+            sxx += w * (x - x_mean) * (x - x_mean);
+            sxy += w * (x - x_mean) * (y - y_mean);
         }
 
+        let slope = sxy / sxx;
+        let b = y_mean - slope * x_mean;
+
+        finished!(self.verbose, self.summary(slope, b));
+
         (slope, b)
     }
+
+    fn fit(&mut self, solver: Solver) -> (f64, f64) {
+        match solver {
+            Solver::LeastSquares => self.least_squares(),
+            Solver::GradientDescent {
+                epoch,
+                learning_rate,
+            } => self.regression(epoch, learning_rate),
+        }
+    }
+
+    fn r_squared(&mut self, f: &dyn Fn(f64) -> f64) -> f64 {
+        self.goodness_of_fit(f).r_squared
+    }
+
+    fn try_regression(
+        &mut self,
+        epoch: i32,
+        learning_rate: f64,
+    ) -> Result<(f64, f64), RegressionError> {
+        self.validate()?;
+        fit_span!("regression", samples = self.x.len(), epoch, learning_rate);
+
+        let mut slope = 0.0;
+        let mut b = 0.0;
+
+        for x in 0..epoch {
+            (slope, b) = self.gradient_descent(slope, b, learning_rate);
+
+            if !slope.is_finite() || !b.is_finite() {
+                return Err(RegressionError::Diverged { epoch: x });
+            }
+
+            epoch!(epoch = x, slope, b);
+        }
+
+        finished!(self.verbose, self.summary(slope, b));
+
+        Ok((slope, b))
+    }
+
+    fn try_least_squares(&mut self) -> Result<(f64, f64), RegressionError> {
+        self.validate()?;
+
+        // With finite data only a constant x leaves the slope undefined
+        let (slope, b) = self.least_squares();
+        if !slope.is_finite() || !b.is_finite() {
+            return Err(RegressionError::Singular);
+        }
+
+        Ok((slope, b))
+    }
+
+    fn try_fit(&mut self, solver: Solver) -> Result<(f64, f64), RegressionError> {
+        match solver {
+            Solver::LeastSquares => self.try_least_squares(),
+            Solver::GradientDescent {
+                epoch,
+                learning_rate,
+            } => self.try_regression(epoch, learning_rate),
+        }
+    }
+
+    fn goodness_of_fit(&mut self, f: &dyn Fn(f64) -> f64) -> GoodnessOfFit {
+        let fitted: Vec<f64> = self.x.iter().map(|x| f(*x)).collect();
+        GoodnessOfFit::weighted(&self.y, &fitted, &self.weights, 1)
+    }
 }
 
 #[cfg(test)]
@@ -75,11 +293,7 @@ mod tests {
 
     #[test]
     fn squared_error_test() {
-        let mut frame = LinearFrame {
-            x: vec![1.0, 2.0, 3.0, 4.0, 5.0],
-            y: vec![1.0, 2.0, 4.0, 4.0, 5.0],
-            verbose: false,
-        };
+        let mut frame = fixtures::frame();
 
         assert_eq!(frame.squared_error(&|x| x), 1.0);
 
@@ -91,11 +305,7 @@ mod tests {
 
     #[test]
     fn mean_squared_error_test() {
-        let mut frame = LinearFrame {
-            x: vec![1.0, 2.0, 3.0, 4.0, 5.0],
-            y: vec![1.0, 2.0, 4.0, 4.0, 5.0],
-            verbose: false,
-        };
+        let mut frame = fixtures::frame();
 
         assert_eq!(frame.mean_squared_error(&|x| x), 0.2);
 
@@ -111,6 +321,7 @@ mod tests {
         let mut frame = LinearFrame {
             x: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0],
             y: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0],
+            weights: None,
             verbose: false,
         };
 
@@ -123,6 +334,7 @@ mod tests {
         let mut frame = LinearFrame {
             x: vec![3.0, 2.0, 1.0, 4.3, 3.4, 8.2, 1.1, 4.5, 6.7],
             y: vec![13.0, 10.0, 7.0, 16.9, 14.2, 28.6, 7.3, 17.5, 24.1],
+            weights: None,
             verbose: false,
         };
 
@@ -131,4 +343,112 @@ mod tests {
         assert!(f64::abs(slope - 3.0) < 0.00001);
         assert!(f64::abs(b - 4.0) < 0.00001);
     }
+
+    #[test]
+    fn least_squares_test() {
+        // f(x) = 3x + 4
+        let mut frame = LinearFrame {
+            x: vec![3.0, 2.0, 1.0, 4.3, 3.4, 8.2, 1.1, 4.5, 6.7],
+            y: vec![13.0, 10.0, 7.0, 16.9, 14.2, 28.6, 7.3, 17.5, 24.1],
+            weights: None,
+            verbose: false,
+        };
+
+        let (slope, b) = frame.least_squares();
+
+        assert!(f64::abs(slope - 3.0) < 1e-10);
+        assert!(f64::abs(b - 4.0) < 1e-10);
+
+        let mut frame = fixtures::frame();
+
+        let (slope, b) = frame.fit(Solver::default());
+
+        assert!(f64::abs(slope - 1.0) < 1e-10);
+        assert!(f64::abs(b - 0.2) < 1e-10);
+
+        let (gd_slope, gd_b) = frame.fit(Solver::GradientDescent {
+            epoch: 100_000,
+            learning_rate: 0.01,
+        });
+
+        assert!(f64::abs(gd_slope - slope) < 1e-6);
+        assert!(f64::abs(gd_b - b) < 1e-6);
+    }
+
+    #[test]
+    fn weighted_test() {
+        // Integer weights act like repeating samples
+        let mut weighted = LinearFrame {
+            x: vec![1.0, 2.0, 3.0, 4.0],
+            y: vec![1.0, 3.0, 2.0, 5.0],
+            weights: Some(vec![1.0, 3.0, 1.0, 2.0]),
+            verbose: false,
+        };
+        let mut repeated = LinearFrame {
+            x: vec![1.0, 2.0, 2.0, 2.0, 3.0, 4.0, 4.0],
+            y: vec![1.0, 3.0, 3.0, 3.0, 2.0, 5.0, 5.0],
+            weights: None,
+            verbose: false,
+        };
+
+        assert_eq!(
+            weighted.squared_error(&|x| x),
+            repeated.squared_error(&|x| x)
+        );
+        assert_eq!(
+            weighted.mean_squared_error(&|x| x),
+            repeated.mean_squared_error(&|x| x)
+        );
+
+        let (slope, b) = weighted.least_squares();
+        let (expected_slope, expected_b) = repeated.least_squares();
+
+        assert!(f64::abs(slope - expected_slope) < 1e-12);
+        assert!(f64::abs(b - expected_b) < 1e-12);
+
+        let (slope, b) = weighted.regression(100_000, 0.01);
+
+        assert!(f64::abs(slope - expected_slope) < 1e-6);
+        assert!(f64::abs(b - expected_b) < 1e-6);
+    }
+
+    #[test]
+    fn errors_test() {
+        assert_eq!(
+            LinearFrame::new(vec![1.0, 2.0], vec![1.0]).err(),
+            Some(RegressionError::LengthMismatch {
+                expected: 1,
+                found: 2
+            })
+        );
+        assert_eq!(
+            LinearFrame::new(vec![], vec![]).err(),
+            Some(RegressionError::Empty)
+        );
+        assert_eq!(
+            LinearFrame::new(vec![1.0, f64::NAN], vec![1.0, 2.0]).err(),
+            Some(RegressionError::NonFinite { index: 1 })
+        );
+        assert_eq!(
+            LinearFrame::weighted(vec![1.0, 2.0], vec![1.0, 2.0], vec![1.0, -1.0]).err(),
+            Some(RegressionError::InvalidWeight { index: 1 })
+        );
+
+        let mut frame = LinearFrame::new(vec![2.0, 2.0, 2.0], vec![1.0, 2.0, 3.0]).unwrap();
+        assert_eq!(frame.try_least_squares(), Err(RegressionError::Singular));
+
+        let mut frame = LinearFrame::new(vec![1.0, 2.0, 3.0], vec![2.0, 4.0, 6.0]).unwrap();
+        assert!(matches!(
+            frame.try_regression(10_000, 10.0),
+            Err(RegressionError::Diverged { .. })
+        ));
+
+        let (slope, b) = frame.try_fit(Solver::default()).unwrap();
+        assert!(f64::abs(slope - 2.0) < 1e-12);
+        assert!(f64::abs(b) < 1e-12);
+
+        // Truncated columns are caught before fitting
+        frame.y.pop();
+        assert!(frame.try_least_squares().is_err());
+    }
 }
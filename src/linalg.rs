@@ -0,0 +1,161 @@
+// Small dense linear algebra helpers shared by the solvers. Matrices are
+// stored the same way frames store samples: one `Vec<f64>` per row.
+
+pub(crate) fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Solves `min ||a * x - b||` with a Householder QR decomposition.
+///
+/// Returns `None` when `a` is empty or does not have full column rank.
+pub(crate) fn least_squares(a: &[Vec<f64>], b: &[f64]) -> Option<Vec<f64>> {
+    let rows = a.len();
+    let cols = a.first().map_or(0, |row| row.len());
+
+    if rows == 0 || rows < cols {
+        return None;
+    }
+
+    // Work on columns so each reflection touches contiguous memory
+    let mut r: Vec<Vec<f64>> = (0..cols)
+        .map(|j| a.iter().map(|row| row[j]).collect())
+        .collect();
+    let mut qtb = b.to_vec();
+
+    for k in 0..cols {
+        let norm = dot(&r[k][k..], &r[k][k..]).sqrt();
+        if norm == 0.0 {
+            return None;
+        }
+
+        let alpha = if r[k][k] > 0.0 { -norm } else { norm };
+
+        let mut v = r[k][k..].to_vec();
+        v[0] -= alpha;
+        let v_norm = dot(&v, &v);
+
+        // Reflect the remaining columns and the right hand side
+        for column in r[k..].iter_mut().chain(std::iter::once(&mut qtb)) {
+            let scale = 2.0 * dot(&v, &column[k..]) / v_norm;
+            for (value, v) in column[k..].iter_mut().zip(&v) {
+                *value -= scale * v;
+            }
+        }
+    }
+
+    let largest = (0..cols).map(|k| r[k][k].abs()).fold(0.0, f64::max);
+    let tolerance = largest * f64::EPSILON * rows as f64;
+
+    let mut x = vec![0.0; cols];
+    for k in (0..cols).rev() {
+        if r[k][k].abs() <= tolerance {
+            return None;
+        }
+
+        let tail: f64 = (k + 1..cols).map(|j| r[j][k] * x[j]).sum();
+        x[k] = (qtb[k] - tail) / r[k][k];
+    }
+
+    Some(x)
+}
+
+/// `aᵀ · a`
+pub(crate) fn gram(a: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let cols = a.first().map_or(0, |row| row.len());
+    let mut product = vec![vec![0.0; cols]; cols];
+
+    for row in a {
+        for i in 0..cols {
+            for j in 0..cols {
+                product[i][j] += row[i] * row[j];
+            }
+        }
+    }
+
+    product
+}
+
+/// `a · x` for a matrix `a` and vector `x`.
+pub(crate) fn multiply(a: &[Vec<f64>], x: &[f64]) -> Vec<f64> {
+    a.iter().map(|row| dot(row, x)).collect()
+}
+
+/// Inverts a square matrix by Gauss-Jordan elimination with partial
+/// pivoting. Returns `None` when the matrix is singular.
+pub(crate) fn inverse(a: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let size = a.len();
+    let mut left = a.to_vec();
+    let mut right: Vec<Vec<f64>> = (0..size)
+        .map(|i| (0..size).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    let largest = a.iter().flatten().map(|x| x.abs()).fold(0.0, f64::max);
+    let tolerance = largest * f64::EPSILON * size as f64;
+
+    for k in 0..size {
+        let pivot = (k..size).max_by(|i, j| left[*i][k].abs().total_cmp(&left[*j][k].abs()))?;
+        if left[pivot][k].abs() <= tolerance {
+            return None;
+        }
+
+        left.swap(k, pivot);
+        right.swap(k, pivot);
+
+        let scale = left[k][k];
+        for j in 0..size {
+            left[k][j] /= scale;
+            right[k][j] /= scale;
+        }
+
+        for i in 0..size {
+            if i != k {
+                let factor = left[i][k];
+                for j in 0..size {
+                    left[i][j] -= factor * left[k][j];
+                    right[i][j] -= factor * right[k][j];
+                }
+            }
+        }
+    }
+
+    Some(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_squares_test() {
+        let a = vec![
+            vec![1.0, 1.0],
+            vec![1.0, 2.0],
+            vec![1.0, 3.0],
+            vec![1.0, 4.0],
+        ];
+        let b = vec![6.0, 5.0, 7.0, 10.0];
+
+        let x = least_squares(&a, &b).unwrap();
+
+        assert!(f64::abs(x[0] - 3.5) < 1e-10);
+        assert!(f64::abs(x[1] - 1.4) < 1e-10);
+
+        let singular = vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]];
+        assert!(least_squares(&singular, &b[..3]).is_none());
+        assert!(least_squares(&[], &[]).is_none());
+    }
+
+    #[test]
+    fn inverse_test() {
+        let a = vec![vec![4.0, 7.0], vec![2.0, 6.0]];
+
+        let inverse = inverse(&a).unwrap();
+
+        assert!(f64::abs(inverse[0][0] - 0.6) < 1e-12);
+        assert!(f64::abs(inverse[0][1] + 0.7) < 1e-12);
+        assert!(f64::abs(inverse[1][0] + 0.2) < 1e-12);
+        assert!(f64::abs(inverse[1][1] - 0.4) < 1e-12);
+
+        assert!(super::inverse(&[vec![1.0, 2.0], vec![2.0, 4.0]]).is_none());
+    }
+}
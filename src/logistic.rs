@@ -0,0 +1,234 @@
+use std::iter::zip;
+
+use crate::linalg;
+use crate::multi::{design_matrix, predict};
+use crate::trace::{epoch, fit_span};
+
+const NEWTON_TOLERANCE: f64 = 1e-10;
+
+pub trait LogisticRegression {
+    fn log_loss(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64;
+    fn mean_log_loss(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64;
+    fn gradient_descent(
+        &mut self,
+        coefficients: &[f64],
+        b: f64,
+        learning_rate: f64,
+    ) -> (Vec<f64>, f64);
+    fn regression(&mut self, epoch: i32, learning_rate: f64) -> LogisticModel;
+    fn newton(&mut self, epoch: i32) -> LogisticModel;
+}
+
+/// A frame for binary outcomes: `y` holds 0/1 labels for the samples in `x`.
+pub struct LogisticFrame {
+    pub y: Vec<f64>,
+    pub x: Vec<Vec<f64>>,
+    pub verbose: bool,
+}
+
+impl LogisticFrame {
+    /// Number of features in each sample.
+    pub fn features(&self) -> usize {
+        self.x.first().map_or(0, |row| row.len())
+    }
+}
+
+/// Fitted logistic model, `P(y = 1) = sigmoid(coefficients · x + b)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogisticModel {
+    pub coefficients: Vec<f64>,
+    pub b: f64,
+}
+
+impl LogisticModel {
+    pub fn predict_proba(&self, x: &[f64]) -> f64 {
+        sigmoid(predict(&self.coefficients, self.b, x))
+    }
+
+    /// Predicts the label 1.0 when the probability reaches `threshold`.
+    pub fn predict(&self, x: &[f64], threshold: f64) -> f64 {
+        if self.predict_proba(x) >= threshold {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+pub fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+impl LogisticRegression for LogisticFrame {
+    fn log_loss(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64 {
+        let mut loss = 0.0;
+
+        for (x, y) in zip(&self.x, &self.y) {
+            // Keep certain but wrong predictions from producing infinity
+            let p = f(x).clamp(1e-15, 1.0 - 1e-15);
+            loss -= y * p.ln() + (1.0 - y) * (1.0 - p).ln();
+        }
+
+        loss
+    }
+
+    fn mean_log_loss(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64 {
+        self.log_loss(f) / self.x.len() as f64
+    }
+
+    fn gradient_descent(
+        &mut self,
+        coefficients: &[f64],
+        b: f64,
+        learning_rate: f64,
+    ) -> (Vec<f64>, f64) {
+        let length = self.x.len() as f64;
+
+        let mut coefficient_gradients = vec![0.0; coefficients.len()];
+        let mut b_gradient = 0.0;
+
+        for (x, y) in zip(&self.x, &self.y) {
+            let residual = y - sigmoid(predict(coefficients, b, x));
+
+            // Partial derivatives of the mean log loss
+            for (gradient, x) in zip(&mut coefficient_gradients, x) {
+                *gradient += -(1.0 / length) * x * residual;
+            }
+            b_gradient += -(1.0 / length) * residual;
+        }
+
+        (
+            zip(coefficients, coefficient_gradients)
+                .map(|(c, gradient)| c - gradient * learning_rate)
+                .collect(),
+            b - b_gradient * learning_rate,
+        )
+    }
+
+    fn regression(&mut self, epoch: i32, learning_rate: f64) -> LogisticModel {
+        fit_span!(
+            "logistic_regression",
+            samples = self.x.len(),
+            epoch,
+            learning_rate
+        );
+
+        let mut coefficients = vec![0.0; self.features()];
+        let mut b = 0.0;
+
+        for x in 0..epoch {
+            (coefficients, b) = self.gradient_descent(&coefficients, b, learning_rate);
+
+            epoch!(epoch = x, ?coefficients, b);
+        }
+
+        LogisticModel { coefficients, b }
+    }
+
+    fn newton(&mut self, epoch: i32) -> LogisticModel {
+        fit_span!("newton", samples = self.x.len(), epoch);
+
+        let design = design_matrix(&self.x);
+        let mut parameters = vec![0.0; self.features() + 1];
+
+        // Iteratively reweighted least squares: every Newton step is a
+        // weighted least squares fit of the working response
+        for x in 0..epoch {
+            let mut rows = Vec::with_capacity(design.len());
+            let mut response = Vec::with_capacity(design.len());
+
+            for (row, y) in zip(&design, &self.y) {
+                let eta = linalg::dot(&parameters, row);
+                let p = sigmoid(eta);
+                let weight = (p * (1.0 - p)).max(1e-10);
+                let root = weight.sqrt();
+
+                rows.push(row.iter().map(|x| x * root).collect());
+                response.push((eta + (y - p) / weight) * root);
+            }
+
+            let next = match linalg::least_squares(&rows, &response) {
+                Some(next) => next,
+                None => vec![f64::NAN; parameters.len()],
+            };
+            let change = zip(&next, &parameters)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max);
+            parameters = next;
+
+            epoch!(iteration = x, ?parameters, change);
+
+            if change <= NEWTON_TOLERANCE || change.is_nan() {
+                break;
+            }
+        }
+
+        LogisticModel {
+            coefficients: parameters[1..].to_vec(),
+            b: parameters[0],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{accuracy, roc_auc};
+
+    fn overlapping() -> LogisticFrame {
+        LogisticFrame {
+            x: vec![
+                vec![0.5],
+                vec![1.0],
+                vec![1.5],
+                vec![2.0],
+                vec![2.5],
+                vec![3.0],
+                vec![3.5],
+                vec![4.0],
+                vec![4.5],
+                vec![5.0],
+            ],
+            y: vec![0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0],
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn log_loss_test() {
+        let mut frame = overlapping();
+
+        let loss = frame.mean_log_loss(&|_| 0.5);
+        assert!(f64::abs(loss - 2.0_f64.ln()) < 1e-12);
+        assert!(frame.log_loss(&|_| 0.0).is_finite());
+    }
+
+    #[test]
+    fn newton_matches_gradient_descent() {
+        let mut frame = overlapping();
+
+        let newton = frame.newton(100);
+        let descent = frame.regression(200_000, 0.5);
+
+        assert!(f64::abs(newton.coefficients[0] - descent.coefficients[0]) < 1e-5);
+        assert!(f64::abs(newton.b - descent.b) < 1e-5);
+
+        // At the optimum the gradient of the log loss vanishes
+        let (coefficients, b) = frame.gradient_descent(&newton.coefficients, newton.b, 1.0);
+        assert!(f64::abs(coefficients[0] - newton.coefficients[0]) < 1e-9);
+        assert!(f64::abs(b - newton.b) < 1e-9);
+    }
+
+    #[test]
+    fn predict_test() {
+        let mut frame = overlapping();
+        let model = frame.newton(100);
+
+        let probabilities: Vec<f64> = frame.x.iter().map(|x| model.predict_proba(x)).collect();
+        let labels: Vec<f64> = frame.x.iter().map(|x| model.predict(x, 0.5)).collect();
+
+        assert!(probabilities.windows(2).all(|p| p[0] < p[1]));
+        assert_eq!(accuracy(&frame.y, &labels), 0.8);
+        assert_eq!(roc_auc(&frame.y, &probabilities), 0.88);
+    }
+}
@@ -0,0 +1,84 @@
+// Classification metrics. Labels and predictions are 0/1 values stored as
+// f64, the same way `LogisticFrame` stores its `y`.
+
+use std::iter::zip;
+
+fn counts(y: &[f64], predicted: &[f64]) -> (f64, f64, f64, f64) {
+    let (mut tp, mut fp, mut tn, mut fn_) = (0.0, 0.0, 0.0, 0.0);
+
+    for (y, p) in zip(y, predicted) {
+        match (*y == 1.0, *p == 1.0) {
+            (true, true) => tp += 1.0,
+            (false, true) => fp += 1.0,
+            (false, false) => tn += 1.0,
+            (true, false) => fn_ += 1.0,
+        }
+    }
+
+    (tp, fp, tn, fn_)
+}
+
+pub fn accuracy(y: &[f64], predicted: &[f64]) -> f64 {
+    let (tp, fp, tn, fn_) = counts(y, predicted);
+    (tp + tn) / (tp + fp + tn + fn_)
+}
+
+pub fn precision(y: &[f64], predicted: &[f64]) -> f64 {
+    let (tp, fp, _, _) = counts(y, predicted);
+    tp / (tp + fp)
+}
+
+pub fn recall(y: &[f64], predicted: &[f64]) -> f64 {
+    let (tp, _, _, fn_) = counts(y, predicted);
+    tp / (tp + fn_)
+}
+
+/// Area under the ROC curve of `scores`, computed as the probability that a
+/// random positive scores above a random negative. Tied scores count half.
+pub fn roc_auc(y: &[f64], scores: &[f64]) -> f64 {
+    let mut pairs: Vec<(f64, f64)> = zip(scores, y).map(|(s, y)| (*s, *y)).collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Sum of the (tie averaged) ranks of the positives
+    let mut positive_ranks = 0.0;
+    let mut i = 0;
+    while i < pairs.len() {
+        let mut j = i;
+        while j < pairs.len() && pairs[j].0 == pairs[i].0 {
+            j += 1;
+        }
+
+        let rank = (i + j + 1) as f64 / 2.0;
+        positive_ranks += rank * pairs[i..j].iter().filter(|(_, y)| *y == 1.0).count() as f64;
+        i = j;
+    }
+
+    let positives = pairs.iter().filter(|(_, y)| *y == 1.0).count() as f64;
+    let negatives = pairs.len() as f64 - positives;
+
+    (positive_ranks - positives * (positives + 1.0) / 2.0) / (positives * negatives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classification_metrics_test() {
+        let y = vec![1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+        let predicted = vec![1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0];
+
+        assert_eq!(accuracy(&y, &predicted), 0.75);
+        assert_eq!(precision(&y, &predicted), 0.75);
+        assert_eq!(recall(&y, &predicted), 0.75);
+    }
+
+    #[test]
+    fn roc_auc_test() {
+        let y = vec![0.0, 0.0, 1.0, 1.0];
+
+        assert_eq!(roc_auc(&y, &[0.1, 0.4, 0.35, 0.8]), 0.75);
+        assert_eq!(roc_auc(&y, &[0.1, 0.2, 0.3, 0.4]), 1.0);
+        assert_eq!(roc_auc(&y, &[0.5, 0.5, 0.5, 0.5]), 0.5);
+    }
+}
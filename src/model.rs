@@ -0,0 +1,324 @@
+use std::iter::zip;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::multi::predict;
+
+/// Version of the serialized model layout. Models written by older versions
+/// of the crate stay loadable; newer ones are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A step applied to the raw features before the coefficients.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Transform {
+    /// Replaces missing or non-finite values with the value of their column.
+    Impute { values: Vec<f64> },
+    /// `(x - mean) / scale` per feature.
+    Standardize { means: Vec<f64>, scales: Vec<f64> },
+    /// Expands a single feature into its powers `x, x², …, x^degree`.
+    Polynomial { degree: usize },
+}
+
+impl Transform {
+    /// Standardization with the column means and standard deviations of `x`.
+    /// Constant columns keep a scale of 1.
+    pub fn standardize(x: &[Vec<f64>]) -> Self {
+        let length = x.len() as f64;
+        let features = x.first().map_or(0, |row| row.len());
+
+        let means: Vec<f64> = (0..features)
+            .map(|j| x.iter().map(|row| row[j]).sum::<f64>() / length)
+            .collect();
+        let scales = means
+            .iter()
+            .enumerate()
+            .map(|(j, mean)| {
+                let variance = x.iter().map(|row| (row[j] - mean).powi(2)).sum::<f64>() / length;
+                if variance > 0.0 {
+                    variance.sqrt()
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+
+        Transform::Standardize { means, scales }
+    }
+
+    pub fn apply(&self, x: &[f64]) -> Vec<f64> {
+        match self {
+            Transform::Impute { values } => zip(x, values)
+                .map(|(x, value)| if x.is_finite() { *x } else { *value })
+                .collect(),
+            Transform::Standardize { means, scales } => zip(zip(x, means), scales)
+                .map(|((x, mean), scale)| (x - mean) / scale)
+                .collect(),
+            Transform::Polynomial { degree } => {
+                (1..=*degree as i32).map(|p| x[0].powi(p)).collect()
+            }
+        }
+    }
+}
+
+/// How a model was fitted.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FitMetadata {
+    pub solver: String,
+    pub samples: usize,
+    /// Epochs of an iterative fit.
+    pub epochs: Option<i32>,
+    /// In-sample goodness of fit, when it was computed.
+    pub mean_squared_error: Option<f64>,
+    pub r_squared: Option<f64>,
+    pub weighted: bool,
+    /// Version of this crate that fitted the model.
+    pub library_version: String,
+}
+
+impl Default for FitMetadata {
+    fn default() -> Self {
+        FitMetadata {
+            solver: String::new(),
+            samples: 0,
+            epochs: None,
+            mean_squared_error: None,
+            r_squared: None,
+            weighted: false,
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// A fitted linear model with everything needed to predict from raw feature
+/// values: the preprocessing, the coefficients and their names.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FittedModel {
+    pub format_version: u32,
+    pub target: String,
+    /// Names of the raw features, before preprocessing.
+    pub features: Vec<String>,
+    /// Applied to the raw features in order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub preprocessing: Vec<Transform>,
+    pub coefficients: Vec<f64>,
+    pub b: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub metadata: FitMetadata,
+}
+
+impl FittedModel {
+    pub fn new(features: Vec<String>, coefficients: Vec<f64>, b: f64) -> Self {
+        FittedModel {
+            format_version: FORMAT_VERSION,
+            target: String::from("y"),
+            features,
+            preprocessing: Vec::new(),
+            coefficients,
+            b,
+            metadata: FitMetadata::default(),
+        }
+    }
+
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = target.to_string();
+        self
+    }
+
+    pub fn with_preprocessing(mut self, transform: Transform) -> Self {
+        self.preprocessing.push(transform);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: FitMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Runs the preprocessing on one sample of raw features.
+    pub fn transform(&self, x: &[f64]) -> Vec<f64> {
+        self.preprocessing
+            .iter()
+            .fold(x.to_vec(), |x, transform| transform.apply(&x))
+    }
+
+    pub fn predict(&self, x: &[f64]) -> f64 {
+        predict(&self.coefficients, self.b, &self.transform(x))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod format {
+    use std::error::Error;
+    use std::fmt;
+
+    use super::{FittedModel, FORMAT_VERSION};
+
+    /// Leads every binary model, followed by the format version.
+    const MAGIC: &[u8; 4] = b"LRM\0";
+
+    #[derive(Debug)]
+    pub enum ModelError {
+        Json(serde_json::Error),
+        Bincode(bincode::Error),
+        /// The bytes do not start with the model header.
+        NotAModel,
+        /// Written by a newer version of the crate.
+        UnsupportedVersion(u32),
+    }
+
+    impl fmt::Display for ModelError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ModelError::Json(error) => write!(f, "{}", error),
+                ModelError::Bincode(error) => write!(f, "{}", error),
+                ModelError::NotAModel => write!(f, "not a serialized model"),
+                ModelError::UnsupportedVersion(version) => write!(
+                    f,
+                    "model format version {} is newer than the supported {}",
+                    version, FORMAT_VERSION
+                ),
+            }
+        }
+    }
+
+    impl Error for ModelError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                ModelError::Json(error) => Some(error),
+                ModelError::Bincode(error) => Some(error),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<serde_json::Error> for ModelError {
+        fn from(error: serde_json::Error) -> Self {
+            ModelError::Json(error)
+        }
+    }
+
+    impl From<bincode::Error> for ModelError {
+        fn from(error: bincode::Error) -> Self {
+            ModelError::Bincode(error)
+        }
+    }
+
+    fn check(version: u32) -> Result<(), ModelError> {
+        if version > FORMAT_VERSION {
+            return Err(ModelError::UnsupportedVersion(version));
+        }
+        Ok(())
+    }
+
+    impl FittedModel {
+        pub fn to_json(&self) -> Result<String, ModelError> {
+            Ok(serde_json::to_string_pretty(self)?)
+        }
+
+        pub fn from_json(json: &str) -> Result<Self, ModelError> {
+            // Check the version before the layout it implies
+            let value: serde_json::Value = serde_json::from_str(json)?;
+            let version = value
+                .get("format_version")
+                .and_then(|version| version.as_u64())
+                .ok_or(ModelError::NotAModel)?;
+            check(u32::try_from(version).unwrap_or(u32::MAX))?;
+
+            Ok(serde_json::from_value(value)?)
+        }
+
+        /// Compact binary encoding behind a header holding the format
+        /// version, so the body can be decoded with the matching layout.
+        pub fn to_bincode(&self) -> Result<Vec<u8>, ModelError> {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend(self.format_version.to_le_bytes());
+            bytes.extend(bincode::serialize(self)?);
+            Ok(bytes)
+        }
+
+        pub fn from_bincode(bytes: &[u8]) -> Result<Self, ModelError> {
+            if bytes.len() < 8 || &bytes[..4] != MAGIC {
+                return Err(ModelError::NotAModel);
+            }
+            check(u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]))?;
+
+            Ok(bincode::deserialize(&bytes[8..])?)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use format::ModelError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> FittedModel {
+        FittedModel::new(vec!["x".to_string()], vec![1.0, 0.5], 2.0)
+            .with_target("y")
+            .with_preprocessing(Transform::Impute { values: vec![3.0] })
+            .with_preprocessing(Transform::Polynomial { degree: 2 })
+            .with_metadata(FitMetadata {
+                solver: "least_squares".to_string(),
+                samples: 10,
+                ..FitMetadata::default()
+            })
+    }
+
+    #[test]
+    fn predict_test() {
+        let model = model();
+
+        assert_eq!(model.transform(&[2.0]), vec![2.0, 4.0]);
+        assert_eq!(model.predict(&[2.0]), 2.0 + 2.0 + 0.5 * 4.0);
+        // Missing values are imputed before the expansion
+        assert_eq!(model.predict(&[f64::NAN]), 2.0 + 3.0 + 0.5 * 9.0);
+
+        let standardize = Transform::standardize(&[vec![1.0, 5.0], vec![3.0, 5.0]]);
+        assert_eq!(
+            standardize,
+            Transform::Standardize {
+                means: vec![2.0, 5.0],
+                scales: vec![1.0, 1.0]
+            }
+        );
+        assert_eq!(standardize.apply(&[4.0, 6.0]), vec![2.0, 1.0]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialization_test() {
+        let model = model();
+
+        let json = model.to_json().unwrap();
+        assert!(json.contains("\"format_version\": 1"));
+        assert_eq!(FittedModel::from_json(&json).unwrap(), model);
+
+        let bytes = model.to_bincode().unwrap();
+        assert_eq!(&bytes[..8], b"LRM\0\x01\0\0\0");
+        assert_eq!(FittedModel::from_bincode(&bytes).unwrap(), model);
+
+        // Missing optional parts fall back to their defaults
+        let minimal = r#"{"format_version": 1, "target": "y", "features": ["x"],
+                          "coefficients": [2.0], "b": 1.0}"#;
+        let loaded = FittedModel::from_json(minimal).unwrap();
+        assert!(loaded.preprocessing.is_empty());
+        assert_eq!(loaded.predict(&[3.0]), 7.0);
+
+        let newer = json.replace("\"format_version\": 1", "\"format_version\": 2");
+        assert!(matches!(
+            FittedModel::from_json(&newer),
+            Err(ModelError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            FittedModel::from_bincode(b"nope"),
+            Err(ModelError::NotAModel)
+        ));
+    }
+}
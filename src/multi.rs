@@ -0,0 +1,527 @@
+use std::iter::zip;
+
+use crate::linalg;
+use crate::optimizer::{Optimizer, Sgd};
+use crate::penalty::Penalty;
+use crate::trace::{epoch, finished, fit_span};
+use crate::{error, GoodnessOfFit, LinearFit, LinearFrame, RegressionError, Solver, Summary};
+
+pub trait MultiRegression {
+    fn squared_error(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64;
+    fn mean_squared_error(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64;
+    fn gradient_descent(
+        &mut self,
+        coefficients: &[f64],
+        b: f64,
+        learning_rate: f64,
+    ) -> (Vec<f64>, f64);
+    fn regression(&mut self, epoch: i32, learning_rate: f64) -> (Vec<f64>, f64);
+    fn least_squares(&mut self) -> (Vec<f64>, f64);
+    fn fit(&mut self, solver: Solver) -> (Vec<f64>, f64);
+    fn r_squared(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64;
+    fn goodness_of_fit(&mut self, f: &dyn Fn(&[f64]) -> f64) -> GoodnessOfFit;
+    fn try_regression(
+        &mut self,
+        epoch: i32,
+        learning_rate: f64,
+    ) -> Result<(Vec<f64>, f64), RegressionError>;
+    fn try_least_squares(&mut self) -> Result<(Vec<f64>, f64), RegressionError>;
+    fn try_fit(&mut self, solver: Solver) -> Result<(Vec<f64>, f64), RegressionError>;
+}
+
+/// A frame with several predictors: each entry of `x` is one sample holding
+/// one value per feature, and `y` holds the matching responses.
+pub struct MultiFrame {
+    pub y: Vec<f64>,
+    pub x: Vec<Vec<f64>>,
+    /// Optional per-sample weights, see `LinearFrame::weights`.
+    pub weights: Option<Vec<f64>>,
+    pub verbose: bool,
+}
+
+impl MultiFrame {
+    /// Builds a frame, checking that every sample has the same number of
+    /// features, that `x` and `y` have the same, non-zero length and that all
+    /// values are finite.
+    pub fn new(x: Vec<Vec<f64>>, y: Vec<f64>) -> Result<Self, RegressionError> {
+        let frame = MultiFrame {
+            y,
+            x,
+            weights: None,
+            verbose: false,
+        };
+        frame.validate()?;
+        Ok(frame)
+    }
+
+    /// Same as `new` with per-sample weights, which must be non-negative.
+    pub fn weighted(
+        x: Vec<Vec<f64>>,
+        y: Vec<f64>,
+        weights: Vec<f64>,
+    ) -> Result<Self, RegressionError> {
+        let frame = MultiFrame {
+            y,
+            x,
+            weights: Some(weights),
+            verbose: false,
+        };
+        frame.validate()?;
+        Ok(frame)
+    }
+
+    pub fn validate(&self) -> Result<(), RegressionError> {
+        let features = self.features();
+        if let Some(row) = self.x.iter().find(|row| row.len() != features) {
+            return Err(RegressionError::LengthMismatch {
+                expected: features,
+                found: row.len(),
+            });
+        }
+
+        error::validate(self.x.len(), &self.y, &self.weights, |i| {
+            self.x[i].iter().all(|x| x.is_finite())
+        })
+    }
+
+    /// Number of features in each sample.
+    pub fn features(&self) -> usize {
+        self.x.first().map_or(0, |row| row.len())
+    }
+
+    /// Summary table for the given coefficients and intercept on this frame.
+    pub fn summary(&self, coefficients: &[f64], b: f64) -> Summary {
+        LinearFit::weighted(&self.x, &self.y, &self.weights, coefficients, b).summary()
+    }
+}
+
+impl From<&LinearFrame> for MultiFrame {
+    fn from(frame: &LinearFrame) -> Self {
+        MultiFrame {
+            y: frame.y.clone(),
+            x: frame.x.iter().map(|x| vec![*x]).collect(),
+            weights: frame.weights.clone(),
+            verbose: frame.verbose,
+        }
+    }
+}
+
+pub(crate) fn predict(coefficients: &[f64], b: f64, x: &[f64]) -> f64 {
+    linalg::dot(coefficients, x) + b
+}
+
+/// Prepends the intercept column of ones to every sample.
+pub(crate) fn design_matrix(x: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    x.iter()
+        .map(|row| std::iter::once(1.0).chain(row.iter().copied()).collect())
+        .collect()
+}
+
+/// Weight of sample `i`, 1 for unweighted frames.
+pub(crate) fn weight(weights: &Option<Vec<f64>>, i: usize) -> f64 {
+    weights.as_ref().map_or(1.0, |weights| weights[i])
+}
+
+/// Sum of the weights of `length` samples.
+pub(crate) fn total_weight(weights: &Option<Vec<f64>>, length: usize) -> f64 {
+    weights
+        .as_ref()
+        .map_or(length as f64, |weights| weights.iter().sum())
+}
+
+/// Scales every row and response by the square root of its weight, which
+/// turns weighted least squares into ordinary least squares.
+pub(crate) fn whiten(
+    rows: &[Vec<f64>],
+    y: &[f64],
+    weights: &Option<Vec<f64>>,
+) -> (Vec<Vec<f64>>, Vec<f64>) {
+    match weights {
+        None => (rows.to_vec(), y.to_vec()),
+        Some(weights) => zip(zip(rows, y), weights)
+            .map(|((row, y), w)| {
+                let root = w.sqrt();
+                (row.iter().map(|x| x * root).collect(), y * root)
+            })
+            .unzip(),
+    }
+}
+
+/// Weighted column means of `x` and the mean of `y`, used to fit an
+/// unpenalized intercept by centering.
+pub(crate) fn means(x: &[Vec<f64>], y: &[f64], weights: &Option<Vec<f64>>) -> (Vec<f64>, f64) {
+    let length = total_weight(weights, x.len());
+    let mut x_means = vec![0.0; x.first().map_or(0, |row| row.len())];
+    let mut y_mean = 0.0;
+
+    for (i, (row, y)) in zip(x, y).enumerate() {
+        let w = weight(weights, i) / length;
+        for (mean, x) in zip(&mut x_means, row) {
+            *mean += w * x;
+        }
+        y_mean += w * y;
+    }
+
+    (x_means, y_mean)
+}
+
+/// Gradient of the mean squared error plus `penalty` with respect to the
+/// coefficients and the intercept.
+pub(crate) fn gradient(
+    x: &[Vec<f64>],
+    y: &[f64],
+    weights: &Option<Vec<f64>>,
+    coefficients: &[f64],
+    b: f64,
+    penalty: &Penalty,
+) -> (Vec<f64>, f64) {
+    batch_gradient(x, y, weights, 0..x.len(), coefficients, b, penalty)
+}
+
+/// Same as `gradient` with the mean squared error taken over the samples in
+/// `batch` only.
+pub(crate) fn batch_gradient(
+    x: &[Vec<f64>],
+    y: &[f64],
+    weights: &Option<Vec<f64>>,
+    batch: impl Iterator<Item = usize>,
+    coefficients: &[f64],
+    b: f64,
+    penalty: &Penalty,
+) -> (Vec<f64>, f64) {
+    let mut coefficient_gradients = vec![0.0; coefficients.len()];
+    let mut b_gradient = 0.0;
+    let mut length = 0.0;
+
+    for i in batch {
+        let w = weight(weights, i);
+        let residual = w * (y[i] - predict(coefficients, b, &x[i]));
+
+        // Partial derivatives with respect to each coefficient
+        for (gradient, x) in zip(&mut coefficient_gradients, &x[i]) {
+            *gradient += -2.0 * x * residual;
+        }
+        // Partial derivative with respect to b
+        b_gradient += -2.0 * residual;
+        length += w;
+    }
+
+    // A batch of zero weight samples contributes nothing
+    let length = f64::max(length, f64::MIN_POSITIVE);
+
+    (
+        zip(coefficient_gradients, coefficients)
+            .map(|(gradient, c)| gradient / length + penalty.gradient(*c))
+            .collect(),
+        b_gradient / length,
+    )
+}
+
+/// One gradient descent step on the mean squared error plus `penalty`.
+pub(crate) fn gradient_step(
+    x: &[Vec<f64>],
+    y: &[f64],
+    weights: &Option<Vec<f64>>,
+    coefficients: &[f64],
+    b: f64,
+    learning_rate: f64,
+    penalty: &Penalty,
+) -> (Vec<f64>, f64) {
+    let (coefficient_gradients, b_gradient) = gradient(x, y, weights, coefficients, b, penalty);
+
+    let mut parameters: Vec<f64> = std::iter::once(b)
+        .chain(coefficients.iter().copied())
+        .collect();
+    let gradients: Vec<f64> = std::iter::once(b_gradient)
+        .chain(coefficient_gradients)
+        .collect();
+    Sgd.step(&mut parameters, &gradients, learning_rate);
+
+    let b = parameters.remove(0);
+    (parameters, b)
+}
+
+impl MultiRegression for MultiFrame {
+    fn squared_error(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64 {
+        let mut error = 0.0;
+
+        for (i, (x, y)) in zip(&self.x, &self.y).enumerate() {
+            let delta = y - f(x);
+            error += weight(&self.weights, i) * delta * delta;
+        }
+
+        error
+    }
+
+    fn mean_squared_error(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64 {
+        self.squared_error(f) / total_weight(&self.weights, self.x.len())
+    }
+
+    fn gradient_descent(
+        &mut self,
+        coefficients: &[f64],
+        b: f64,
+        learning_rate: f64,
+    ) -> (Vec<f64>, f64) {
+        gradient_step(
+            &self.x,
+            &self.y,
+            &self.weights,
+            coefficients,
+            b,
+            learning_rate,
+            &Penalty::None,
+        )
+    }
+
+    fn regression(&mut self, epoch: i32, learning_rate: f64) -> (Vec<f64>, f64) {
+        fit_span!(
+            "regression",
+            samples = self.x.len(),
+            features = self.features(),
+            epoch,
+            learning_rate
+        );
+
+        let mut coefficients = vec![0.0; self.features()];
+        let mut b = 0.0;
+
+        for x in 0..epoch {
+            (coefficients, b) = self.gradient_descent(&coefficients, b, learning_rate);
+
+            epoch!(epoch = x, ?coefficients, b);
+        }
+
+        finished!(self.verbose, self.summary(&coefficients, b));
+
+        (coefficients, b)
+    }
+
+    fn least_squares(&mut self) -> (Vec<f64>, f64) {
+        fit_span!(
+            "least_squares",
+            samples = self.x.len(),
+            features = self.features()
+        );
+
+        let (design, response) = whiten(&design_matrix(&self.x), &self.y, &self.weights);
+
+        // A rank deficient design has no unique solution
+        let solution = linalg::least_squares(&design, &response)
+            .unwrap_or_else(|| vec![f64::NAN; self.features() + 1]);
+
+        let (b, coefficients) = (solution[0], solution[1..].to_vec());
+
+        finished!(self.verbose, self.summary(&coefficients, b));
+
+        (coefficients, b)
+    }
+
+    fn fit(&mut self, solver: Solver) -> (Vec<f64>, f64) {
+        match solver {
+            Solver::LeastSquares => self.least_squares(),
+            Solver::GradientDescent {
+                epoch,
+                learning_rate,
+            } => self.regression(epoch, learning_rate),
+        }
+    }
+
+    fn r_squared(&mut self, f: &dyn Fn(&[f64]) -> f64) -> f64 {
+        self.goodness_of_fit(f).r_squared
+    }
+
+    fn goodness_of_fit(&mut self, f: &dyn Fn(&[f64]) -> f64) -> GoodnessOfFit {
+        let fitted: Vec<f64> = self.x.iter().map(|x| f(x)).collect();
+        GoodnessOfFit::weighted(&self.y, &fitted, &self.weights, self.features())
+    }
+
+    fn try_regression(
+        &mut self,
+        epoch: i32,
+        learning_rate: f64,
+    ) -> Result<(Vec<f64>, f64), RegressionError> {
+        self.validate()?;
+        fit_span!(
+            "regression",
+            samples = self.x.len(),
+            features = self.features(),
+            epoch,
+            learning_rate
+        );
+
+        let mut coefficients = vec![0.0; self.features()];
+        let mut b = 0.0;
+
+        for x in 0..epoch {
+            (coefficients, b) = self.gradient_descent(&coefficients, b, learning_rate);
+
+            if !b.is_finite() || coefficients.iter().any(|c| !c.is_finite()) {
+                return Err(RegressionError::Diverged { epoch: x });
+            }
+
+            epoch!(epoch = x, ?coefficients, b);
+        }
+
+        finished!(self.verbose, self.summary(&coefficients, b));
+
+        Ok((coefficients, b))
+    }
+
+    fn try_least_squares(&mut self) -> Result<(Vec<f64>, f64), RegressionError> {
+        self.validate()?;
+        fit_span!(
+            "least_squares",
+            samples = self.x.len(),
+            features = self.features()
+        );
+
+        let (design, response) = whiten(&design_matrix(&self.x), &self.y, &self.weights);
+        let solution =
+            linalg::least_squares(&design, &response).ok_or(RegressionError::Singular)?;
+
+        let (b, coefficients) = (solution[0], solution[1..].to_vec());
+
+        finished!(self.verbose, self.summary(&coefficients, b));
+
+        Ok((coefficients, b))
+    }
+
+    fn try_fit(&mut self, solver: Solver) -> Result<(Vec<f64>, f64), RegressionError> {
+        match solver {
+            Solver::LeastSquares => self.try_least_squares(),
+            Solver::GradientDescent {
+                epoch,
+                learning_rate,
+            } => self.try_regression(epoch, learning_rate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Regression;
+
+    // f(x1, x2) = 2x1 - x2 + 3
+    fn plane() -> MultiFrame {
+        MultiFrame {
+            x: vec![
+                vec![1.0, 2.0],
+                vec![2.0, 1.0],
+                vec![3.0, 4.0],
+                vec![4.0, 3.0],
+                vec![5.0, 5.0],
+                vec![0.0, 1.0],
+            ],
+            y: vec![3.0, 6.0, 5.0, 8.0, 8.0, 2.0],
+            weights: None,
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn squared_error_test() {
+        let mut frame = plane();
+
+        assert_eq!(frame.squared_error(&|x| 2.0 * x[0] - x[1] + 3.0), 0.0);
+        assert_eq!(frame.squared_error(&|x| 2.0 * x[0] - x[1] + 4.0), 6.0);
+        assert_eq!(frame.mean_squared_error(&|x| 2.0 * x[0] - x[1] + 4.0), 1.0);
+    }
+
+    #[test]
+    fn least_squares_test() {
+        let mut frame = plane();
+
+        let (coefficients, b) = frame.least_squares();
+
+        assert!(f64::abs(coefficients[0] - 2.0) < 1e-10);
+        assert!(f64::abs(coefficients[1] + 1.0) < 1e-10);
+        assert!(f64::abs(b - 3.0) < 1e-10);
+    }
+
+    #[test]
+    fn regression_test() {
+        let mut frame = plane();
+
+        let (coefficients, b) = frame.regression(200_000, 0.01);
+
+        assert!(f64::abs(coefficients[0] - 2.0) < 0.00001);
+        assert!(f64::abs(coefficients[1] + 1.0) < 0.00001);
+        assert!(f64::abs(b - 3.0) < 0.00001);
+    }
+
+    #[test]
+    fn single_column_matches_linear_frame() {
+        let mut linear = LinearFrame {
+            x: vec![3.0, 2.0, 1.0, 4.3, 3.4, 8.2, 1.1, 4.5, 6.7],
+            y: vec![13.0, 10.0, 7.0, 16.9, 14.2, 28.6, 7.3, 17.5, 24.1],
+            weights: None,
+            verbose: false,
+        };
+        let mut multi = MultiFrame::from(&linear);
+
+        let (slope, b) = linear.least_squares();
+        let (coefficients, multi_b) = multi.least_squares();
+
+        assert!(f64::abs(coefficients[0] - slope) < 1e-10);
+        assert!(f64::abs(multi_b - b) < 1e-10);
+    }
+
+    #[test]
+    fn weighted_test() {
+        let mut frame = plane();
+        // Corrupt one sample and give it almost no weight
+        frame.y[0] += 10.0;
+        frame.weights = Some(vec![1e-12, 1.0, 1.0, 1.0, 1.0, 1.0]);
+
+        let (coefficients, b) = frame.least_squares();
+
+        assert!(f64::abs(coefficients[0] - 2.0) < 1e-9);
+        assert!(f64::abs(coefficients[1] + 1.0) < 1e-9);
+        assert!(f64::abs(b - 3.0) < 1e-9);
+        assert!(frame.mean_squared_error(&|x| 2.0 * x[0] - x[1] + 3.0) < 1e-9);
+    }
+
+    #[test]
+    fn errors_test() {
+        assert_eq!(
+            MultiFrame::new(vec![vec![1.0, 2.0], vec![3.0]], vec![1.0, 2.0]).err(),
+            Some(RegressionError::LengthMismatch {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            MultiFrame::new(vec![vec![1.0], vec![f64::INFINITY]], vec![1.0, 2.0]).err(),
+            Some(RegressionError::NonFinite { index: 1 })
+        );
+
+        // The second feature is twice the first
+        let mut frame = MultiFrame::new(
+            vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]],
+            vec![1.0, 2.0, 4.0],
+        )
+        .unwrap();
+        assert_eq!(frame.try_least_squares(), Err(RegressionError::Singular));
+
+        let mut frame = plane();
+        assert!(matches!(
+            frame.try_fit(Solver::GradientDescent {
+                epoch: 1000,
+                learning_rate: 1.0
+            }),
+            Err(RegressionError::Diverged { .. })
+        ));
+        assert_eq!(frame.try_least_squares().ok(), Some(frame.least_squares()));
+
+        let mut empty = MultiFrame {
+            x: vec![],
+            y: vec![],
+            weights: None,
+            verbose: false,
+        };
+        let (coefficients, b) = empty.least_squares();
+        assert!(coefficients.is_empty() && b.is_nan());
+    }
+}
@@ -0,0 +1,308 @@
+use std::fmt::Debug;
+use std::iter::zip;
+
+/// Update rule for gradient based training. Implementations keep whatever
+/// running state they need between steps, one entry per parameter.
+pub trait Optimizer: Debug {
+    /// Moves `parameters` against `gradient`, the gradient of the loss at
+    /// `parameters`.
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64);
+    /// Forgets the accumulated state before a new fit.
+    fn reset(&mut self);
+}
+
+/// Zeroed state for `length` parameters, kept as is when already sized.
+fn state(state: &mut Vec<f64>, length: usize) -> &mut [f64] {
+    if state.len() != length {
+        *state = vec![0.0; length];
+    }
+    state
+}
+
+/// Plain gradient descent, `p -= lr · g`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sgd;
+
+impl Optimizer for Sgd {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        for (p, g) in zip(parameters, gradient) {
+            *p -= learning_rate * g;
+        }
+    }
+
+    fn reset(&mut self) {}
+}
+
+/// Heavy ball momentum, `v = μ · v + g` and `p -= lr · v`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Momentum {
+    pub momentum: f64,
+    velocity: Vec<f64>,
+}
+
+impl Momentum {
+    pub fn new(momentum: f64) -> Self {
+        Momentum {
+            momentum,
+            velocity: Vec::new(),
+        }
+    }
+}
+
+impl Default for Momentum {
+    fn default() -> Self {
+        Momentum::new(0.9)
+    }
+}
+
+impl Optimizer for Momentum {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        let velocity = state(&mut self.velocity, parameters.len());
+
+        for ((p, g), v) in zip(zip(parameters, gradient), velocity) {
+            *v = self.momentum * *v + g;
+            *p -= learning_rate * *v;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.velocity.clear();
+    }
+}
+
+/// Nesterov accelerated gradient. The look ahead gradient is approximated
+/// from the current one, `p -= lr · (g + μ · v)`, so each step needs a
+/// single gradient evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nesterov {
+    pub momentum: f64,
+    velocity: Vec<f64>,
+}
+
+impl Nesterov {
+    pub fn new(momentum: f64) -> Self {
+        Nesterov {
+            momentum,
+            velocity: Vec::new(),
+        }
+    }
+}
+
+impl Default for Nesterov {
+    fn default() -> Self {
+        Nesterov::new(0.9)
+    }
+}
+
+impl Optimizer for Nesterov {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        let velocity = state(&mut self.velocity, parameters.len());
+
+        for ((p, g), v) in zip(zip(parameters, gradient), velocity) {
+            *v = self.momentum * *v + g;
+            *p -= learning_rate * (g + self.momentum * *v);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.velocity.clear();
+    }
+}
+
+/// Scales each parameter's step by the root of its summed squared gradients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaGrad {
+    pub epsilon: f64,
+    squares: Vec<f64>,
+}
+
+impl AdaGrad {
+    pub fn new(epsilon: f64) -> Self {
+        AdaGrad {
+            epsilon,
+            squares: Vec::new(),
+        }
+    }
+}
+
+impl Default for AdaGrad {
+    fn default() -> Self {
+        AdaGrad::new(1e-8)
+    }
+}
+
+impl Optimizer for AdaGrad {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        let squares = state(&mut self.squares, parameters.len());
+
+        for ((p, g), s) in zip(zip(parameters, gradient), squares) {
+            *s += g * g;
+            *p -= learning_rate * g / (s.sqrt() + self.epsilon);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.squares.clear();
+    }
+}
+
+/// Like `AdaGrad` with an exponentially decaying average of the squared
+/// gradients, so steps do not shrink forever.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RMSProp {
+    pub decay: f64,
+    pub epsilon: f64,
+    squares: Vec<f64>,
+}
+
+impl RMSProp {
+    pub fn new(decay: f64, epsilon: f64) -> Self {
+        RMSProp {
+            decay,
+            epsilon,
+            squares: Vec::new(),
+        }
+    }
+}
+
+impl Default for RMSProp {
+    fn default() -> Self {
+        RMSProp::new(0.9, 1e-8)
+    }
+}
+
+impl Optimizer for RMSProp {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        let squares = state(&mut self.squares, parameters.len());
+
+        for ((p, g), s) in zip(zip(parameters, gradient), squares) {
+            *s = self.decay * *s + (1.0 - self.decay) * g * g;
+            *p -= learning_rate * g / (s.sqrt() + self.epsilon);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.squares.clear();
+    }
+}
+
+/// Adaptive moment estimation (Kingma & Ba, 2015) with bias corrected first
+/// and second moments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adam {
+    pub beta1: f64,
+    pub beta2: f64,
+    pub epsilon: f64,
+    moments: Vec<f64>,
+    squares: Vec<f64>,
+    steps: i32,
+}
+
+impl Adam {
+    pub fn new(beta1: f64, beta2: f64, epsilon: f64) -> Self {
+        Adam {
+            beta1,
+            beta2,
+            epsilon,
+            moments: Vec::new(),
+            squares: Vec::new(),
+            steps: 0,
+        }
+    }
+}
+
+impl Default for Adam {
+    fn default() -> Self {
+        Adam::new(0.9, 0.999, 1e-8)
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        let length = parameters.len();
+        if self.moments.len() != length {
+            self.steps = 0;
+        }
+        let moments = state(&mut self.moments, length);
+        let squares = state(&mut self.squares, length);
+
+        self.steps += 1;
+        let first = 1.0 - self.beta1.powi(self.steps);
+        let second = 1.0 - self.beta2.powi(self.steps);
+
+        for (((p, g), m), s) in zip(zip(zip(parameters, gradient), moments), squares) {
+            *m = self.beta1 * *m + (1.0 - self.beta1) * g;
+            *s = self.beta2 * *s + (1.0 - self.beta2) * g * g;
+            *p -= learning_rate * (*m / first) / ((*s / second).sqrt() + self.epsilon);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.moments.clear();
+        self.squares.clear();
+        self.steps = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimizes x² + 100 y², whose gradient is (2x, 200y)
+    fn minimize(optimizer: &mut dyn Optimizer, learning_rate: f64, steps: usize) -> Vec<f64> {
+        let mut parameters = vec![1.0, 1.0];
+        for _ in 0..steps {
+            let gradient = [2.0 * parameters[0], 200.0 * parameters[1]];
+            optimizer.step(&mut parameters, &gradient, learning_rate);
+        }
+        parameters
+    }
+
+    #[test]
+    fn sgd_test() {
+        let mut parameters = vec![1.0, 2.0];
+        Sgd.step(&mut parameters, &[0.5, -1.0], 0.1);
+
+        assert_eq!(parameters, vec![0.95, 2.1]);
+    }
+
+    #[test]
+    fn optimizers_converge() {
+        let optimizers: Vec<(Box<dyn Optimizer>, f64)> = vec![
+            (Box::new(Sgd), 0.005),
+            (Box::new(Momentum::default()), 0.005),
+            (Box::new(Nesterov::default()), 0.005),
+            (Box::new(AdaGrad::default()), 0.5),
+            (Box::new(RMSProp::default()), 0.01),
+            (Box::new(Adam::default()), 0.05),
+        ];
+
+        for (mut optimizer, learning_rate) in optimizers {
+            let parameters = minimize(optimizer.as_mut(), learning_rate, 2000);
+
+            assert!(
+                parameters.iter().all(|p| p.abs() < 1e-2),
+                "{:?} stopped at {:?}",
+                optimizer,
+                parameters
+            );
+        }
+    }
+
+    #[test]
+    fn momentum_is_faster_on_badly_scaled_problems() {
+        let sgd = minimize(&mut Sgd, 0.005, 200);
+        let momentum = minimize(&mut Momentum::default(), 0.005, 200);
+
+        assert!(momentum[0].abs() < sgd[0].abs() / 10.0);
+    }
+
+    #[test]
+    fn reset_test() {
+        let mut adam = Adam::default();
+        let first = minimize(&mut adam, 0.05, 10);
+
+        adam.reset();
+        assert_eq!(minimize(&mut adam, 0.05, 10), first);
+    }
+}
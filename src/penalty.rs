@@ -0,0 +1,76 @@
+/// Penalty added to the mean squared error objective. The intercept is never
+/// penalized.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Penalty {
+    #[default]
+    None,
+    /// `lambda · Σ |β|`
+    L1(f64),
+    /// `lambda · Σ β²`
+    L2(f64),
+    /// `lambda · (l1_ratio · Σ |β| + (1 - l1_ratio) · Σ β²)`
+    ElasticNet { lambda: f64, l1_ratio: f64 },
+}
+
+impl Penalty {
+    pub fn value(&self, coefficients: &[f64]) -> f64 {
+        let l1 = || coefficients.iter().map(|c| c.abs()).sum::<f64>();
+        let l2 = || coefficients.iter().map(|c| c * c).sum::<f64>();
+
+        match *self {
+            Penalty::None => 0.0,
+            Penalty::L1(lambda) => lambda * l1(),
+            Penalty::L2(lambda) => lambda * l2(),
+            Penalty::ElasticNet { lambda, l1_ratio } => {
+                lambda * (l1_ratio * l1() + (1.0 - l1_ratio) * l2())
+            }
+        }
+    }
+
+    /// Partial derivative of the penalty with respect to one coefficient. The
+    /// L1 term is not differentiable at zero, so its subgradient 0 is used.
+    pub fn gradient(&self, coefficient: f64) -> f64 {
+        let sign = if coefficient == 0.0 {
+            0.0
+        } else {
+            coefficient.signum()
+        };
+
+        match *self {
+            Penalty::None => 0.0,
+            Penalty::L1(lambda) => lambda * sign,
+            Penalty::L2(lambda) => 2.0 * lambda * coefficient,
+            Penalty::ElasticNet { lambda, l1_ratio } => {
+                lambda * (l1_ratio * sign + 2.0 * (1.0 - l1_ratio) * coefficient)
+            }
+        }
+    }
+}
+
+/// One fit along a regularization path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathStep {
+    pub lambda: f64,
+    pub coefficients: Vec<f64>,
+    pub b: f64,
+    /// Indices of the coefficients that are not exactly zero.
+    pub active: Vec<usize>,
+}
+
+impl PathStep {
+    pub fn new(lambda: f64, coefficients: Vec<f64>, b: f64) -> Self {
+        let active = coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c != 0.0)
+            .map(|(i, _)| i)
+            .collect();
+
+        PathStep {
+            lambda,
+            coefficients,
+            b,
+            active,
+        }
+    }
+}
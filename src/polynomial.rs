@@ -0,0 +1,93 @@
+use crate::{LinearFrame, MultiFrame, MultiRegression, Regression, Solver};
+
+pub trait PolynomialRegression {
+    fn polynomial_features(&self, degree: usize) -> MultiFrame;
+    fn polynomial_regression(&mut self, degree: usize, solver: Solver) -> Vec<f64>;
+    fn select_degree(&mut self, validation: &mut LinearFrame, max_degree: usize) -> usize;
+}
+
+/// Evaluates `a0 + a1·x + … + ad·x^d` for coefficients `[a0, a1, …, ad]`.
+pub fn evaluate(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, a| acc * x + a)
+}
+
+impl PolynomialRegression for LinearFrame {
+    fn polynomial_features(&self, degree: usize) -> MultiFrame {
+        MultiFrame {
+            y: self.y.clone(),
+            x: self
+                .x
+                .iter()
+                .map(|x| (1..=degree).map(|power| x.powi(power as i32)).collect())
+                .collect(),
+            weights: self.weights.clone(),
+            verbose: self.verbose,
+        }
+    }
+
+    fn polynomial_regression(&mut self, degree: usize, solver: Solver) -> Vec<f64> {
+        let (coefficients, b) = self.polynomial_features(degree).fit(solver);
+
+        std::iter::once(b).chain(coefficients).collect()
+    }
+
+    fn select_degree(&mut self, validation: &mut LinearFrame, max_degree: usize) -> usize {
+        let mut best = (1, f64::INFINITY);
+
+        for degree in 1..=max_degree {
+            let coefficients = self.polynomial_regression(degree, Solver::LeastSquares);
+            let error = validation.mean_squared_error(&|x| evaluate(&coefficients, x));
+
+            if error < best.1 {
+                best = (degree, error);
+            }
+        }
+
+        best.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // f(x) = 1 - 2x + 0.5x^2
+    fn parabola(x: Vec<f64>) -> LinearFrame {
+        LinearFrame {
+            y: x.iter().map(|x| 1.0 - 2.0 * x + 0.5 * x * x).collect(),
+            x,
+            weights: None,
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn evaluate_test() {
+        assert_eq!(evaluate(&[1.0, -2.0, 0.5], 2.0), -1.0);
+        assert_eq!(evaluate(&[], 2.0), 0.0);
+    }
+
+    #[test]
+    fn polynomial_regression_test() {
+        let mut frame = parabola(vec![-3.0, -2.0, -1.0, 0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        let coefficients = frame.polynomial_regression(2, Solver::LeastSquares);
+
+        assert_eq!(coefficients.len(), 3);
+        assert!(f64::abs(coefficients[0] - 1.0) < 1e-10);
+        assert!(f64::abs(coefficients[1] + 2.0) < 1e-10);
+        assert!(f64::abs(coefficients[2] - 0.5) < 1e-10);
+    }
+
+    #[test]
+    fn select_degree_test() {
+        let mut train = parabola(vec![-3.0, -2.0, -1.0, 0.0, 1.0, 2.0, 3.0, 4.0]);
+        // Noise keeps higher degrees from fitting the training data exactly
+        for (i, y) in train.y.iter_mut().enumerate() {
+            *y += if i % 2 == 0 { 0.1 } else { -0.1 };
+        }
+        let mut validation = parabola(vec![-2.5, -0.5, 0.5, 1.5, 3.5, 5.0]);
+
+        assert_eq!(train.select_degree(&mut validation, 5), 2);
+    }
+}
@@ -0,0 +1,209 @@
+use std::iter::zip;
+
+use crate::linalg;
+use crate::multi::{gradient_step, means, total_weight, whiten};
+use crate::penalty::{PathStep, Penalty};
+use crate::trace::{epoch, fit_span, progress};
+use crate::{LinearFrame, MultiFrame};
+
+/// L2 regularized regression, minimizing `mean squared error + lambda · Σ β²`.
+pub trait Ridge {
+    fn ridge_gradient_descent(
+        &mut self,
+        coefficients: &[f64],
+        b: f64,
+        learning_rate: f64,
+        lambda: f64,
+    ) -> (Vec<f64>, f64);
+    fn ridge_regression(&mut self, epoch: i32, learning_rate: f64, lambda: f64) -> (Vec<f64>, f64);
+    fn ridge_least_squares(&mut self, lambda: f64) -> (Vec<f64>, f64);
+    fn ridge_path(&mut self, lambdas: &[f64]) -> Vec<PathStep>;
+}
+
+impl Ridge for MultiFrame {
+    fn ridge_gradient_descent(
+        &mut self,
+        coefficients: &[f64],
+        b: f64,
+        learning_rate: f64,
+        lambda: f64,
+    ) -> (Vec<f64>, f64) {
+        gradient_step(
+            &self.x,
+            &self.y,
+            &self.weights,
+            coefficients,
+            b,
+            learning_rate,
+            &Penalty::L2(lambda),
+        )
+    }
+
+    fn ridge_regression(&mut self, epoch: i32, learning_rate: f64, lambda: f64) -> (Vec<f64>, f64) {
+        fit_span!(
+            "ridge_regression",
+            samples = self.x.len(),
+            features = self.features(),
+            epoch,
+            learning_rate,
+            lambda
+        );
+
+        let mut coefficients = vec![0.0; self.features()];
+        let mut b = 0.0;
+
+        for x in 0..epoch {
+            (coefficients, b) =
+                self.ridge_gradient_descent(&coefficients, b, learning_rate, lambda);
+
+            epoch!(epoch = x, ?coefficients, b);
+        }
+
+        (coefficients, b)
+    }
+
+    fn ridge_least_squares(&mut self, lambda: f64) -> (Vec<f64>, f64) {
+        fit_span!(
+            "ridge_least_squares",
+            samples = self.x.len(),
+            features = self.features(),
+            lambda
+        );
+
+        let features = self.features();
+        let (x_means, y_mean) = means(&self.x, &self.y, &self.weights);
+
+        // Centering leaves the intercept out of the penalty. Appending
+        // sqrt(Σw · lambda) · I below the centered design turns the ridge
+        // objective into an ordinary least squares problem.
+        let centered: Vec<Vec<f64>> = self
+            .x
+            .iter()
+            .map(|row| zip(row, &x_means).map(|(x, mean)| x - mean).collect())
+            .collect();
+        let response: Vec<f64> = self.y.iter().map(|y| y - y_mean).collect();
+        let (mut design, mut response) = whiten(&centered, &response, &self.weights);
+
+        let scale = (total_weight(&self.weights, self.x.len()) * lambda).sqrt();
+        for j in 0..features {
+            let mut row = vec![0.0; features];
+            row[j] = scale;
+            design.push(row);
+            response.push(0.0);
+        }
+
+        let coefficients =
+            linalg::least_squares(&design, &response).unwrap_or_else(|| vec![f64::NAN; features]);
+        let b = y_mean - linalg::dot(&coefficients, &x_means);
+
+        progress!(
+            self.verbose,
+            [?coefficients, b, "fit finished"],
+            "y = {:?} · x + {}",
+            coefficients,
+            b
+        );
+
+        (coefficients, b)
+    }
+
+    fn ridge_path(&mut self, lambdas: &[f64]) -> Vec<PathStep> {
+        lambdas
+            .iter()
+            .map(|&lambda| {
+                let (coefficients, b) = self.ridge_least_squares(lambda);
+                PathStep::new(lambda, coefficients, b)
+            })
+            .collect()
+    }
+}
+
+impl Ridge for LinearFrame {
+    fn ridge_gradient_descent(
+        &mut self,
+        coefficients: &[f64],
+        b: f64,
+        learning_rate: f64,
+        lambda: f64,
+    ) -> (Vec<f64>, f64) {
+        MultiFrame::from(&*self).ridge_gradient_descent(coefficients, b, learning_rate, lambda)
+    }
+
+    fn ridge_regression(&mut self, epoch: i32, learning_rate: f64, lambda: f64) -> (Vec<f64>, f64) {
+        MultiFrame::from(&*self).ridge_regression(epoch, learning_rate, lambda)
+    }
+
+    fn ridge_least_squares(&mut self, lambda: f64) -> (Vec<f64>, f64) {
+        MultiFrame::from(&*self).ridge_least_squares(lambda)
+    }
+
+    fn ridge_path(&mut self, lambdas: &[f64]) -> Vec<PathStep> {
+        MultiFrame::from(&*self).ridge_path(lambdas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame;
+    use crate::MultiRegression;
+
+    // Two nearly collinear predictors
+    fn correlated() -> MultiFrame {
+        MultiFrame {
+            x: vec![
+                vec![1.0, 1.01],
+                vec![2.0, 1.98],
+                vec![3.0, 3.02],
+                vec![4.0, 3.99],
+                vec![5.0, 5.01],
+            ],
+            y: vec![2.1, 3.9, 6.2, 7.8, 10.1],
+            weights: None,
+            verbose: false,
+        }
+    }
+
+    #[test]
+    fn ridge_least_squares_test() {
+        let mut frame = correlated();
+
+        let (ols, ols_b) = frame.least_squares();
+        let (zero, zero_b) = frame.ridge_least_squares(0.0);
+
+        assert!(f64::abs(ols[0] - zero[0]) < 1e-8);
+        assert!(f64::abs(ols[1] - zero[1]) < 1e-8);
+        assert!(f64::abs(ols_b - zero_b) < 1e-8);
+
+        let (ridge, _) = frame.ridge_least_squares(0.1);
+        let norm = |c: &[f64]| c.iter().map(|c| c * c).sum::<f64>();
+
+        assert!(norm(&ridge) < norm(&ols));
+        assert!(f64::abs(ridge[0] - ridge[1]) < f64::abs(ols[0] - ols[1]));
+    }
+
+    #[test]
+    fn ridge_regression_matches_closed_form() {
+        let mut frame = correlated();
+
+        let (closed, closed_b) = frame.ridge_least_squares(0.5);
+        let (descent, descent_b) = frame.ridge_regression(100_000, 0.01, 0.5);
+
+        assert!(f64::abs(closed[0] - descent[0]) < 1e-6);
+        assert!(f64::abs(closed[1] - descent[1]) < 1e-6);
+        assert!(f64::abs(closed_b - descent_b) < 1e-6);
+    }
+
+    #[test]
+    fn ridge_path_test() {
+        let mut frame = frame();
+
+        let path = frame.ridge_path(&[0.0, 0.1, 1.0, 10.0]);
+
+        assert_eq!(path.len(), 4);
+        assert!(f64::abs(path[0].coefficients[0] - 1.0) < 1e-10);
+        for pair in path.windows(2) {
+            assert!(pair[1].coefficients[0] < pair[0].coefficients[0]);
+        }
+    }
+}
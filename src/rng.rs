@@ -0,0 +1,69 @@
+/// SplitMix64 (Steele, Lea & Flood, 2014). Small, fast and good enough for
+/// shuffling samples; the same seed always gives the same sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..bound`, without modulo bias.
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        let bound = bound as u64;
+        let zone = u64::MAX - u64::MAX % bound;
+
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % bound) as usize;
+            }
+        }
+    }
+
+    /// Fisher-Yates shuffle.
+    pub(crate) fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitmix_test() {
+        // Reference values for seed 1234567
+        let mut rng = Rng::new(1234567);
+
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+    }
+
+    #[test]
+    fn shuffle_test() {
+        let mut values: Vec<usize> = (0..100).collect();
+        Rng::new(7).shuffle(&mut values);
+
+        let mut again: Vec<usize> = (0..100).collect();
+        Rng::new(7).shuffle(&mut again);
+        assert_eq!(values, again);
+        assert_ne!(values, (0..100).collect::<Vec<_>>());
+
+        values.sort();
+        assert_eq!(values, (0..100).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::inference::coefficient_table;
+use crate::linalg;
+use crate::{LinearFit, RegressionError};
+
+/// Estimator of the covariance of the least squares parameters.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Covariance {
+    /// `σ² (XᵀX)⁻¹`, valid for independent homoskedastic errors.
+    #[default]
+    Classic,
+    /// White's heteroskedasticity consistent estimator.
+    HC0,
+    /// HC0 scaled by `n / (n - p)`.
+    HC1,
+    /// HC0 with squared residuals divided by `1 - hᵢ`.
+    HC2,
+    /// HC0 with squared residuals divided by `(1 - hᵢ)²`.
+    HC3,
+    /// Heteroskedasticity and autocorrelation consistent estimator with
+    /// Bartlett weights over `lags` lags.
+    NeweyWest { lags: usize },
+    /// Cluster robust estimator, with one group label per observation.
+    Clustered(Vec<String>),
+}
+
+impl Covariance {
+    /// Newey and West's rule of thumb `floor(4 (n / 100)^(2/9))`.
+    pub fn default_lags(observations: usize) -> usize {
+        (4.0 * (observations as f64 / 100.0).powf(2.0 / 9.0)).floor() as usize
+    }
+}
+
+impl fmt::Display for Covariance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Covariance::Classic => write!(f, "nonrobust"),
+            Covariance::HC0 => write!(f, "HC0"),
+            Covariance::HC1 => write!(f, "HC1"),
+            Covariance::HC2 => write!(f, "HC2"),
+            Covariance::HC3 => write!(f, "HC3"),
+            Covariance::NeweyWest { lags } => write!(f, "HAC ({} lags)", lags),
+            Covariance::Clustered(_) => write!(f, "cluster"),
+        }
+    }
+}
+
+fn add_outer(meat: &mut [Vec<f64>], a: &[f64], b: &[f64], scale: f64) {
+    for (row, a) in meat.iter_mut().zip(a) {
+        for (value, b) in row.iter_mut().zip(b) {
+            *value += scale * a * b;
+        }
+    }
+}
+
+impl LinearFit {
+    /// Recomputes the standard errors, t-statistics and p-values of this fit
+    /// with another covariance estimator.
+    ///
+    /// Clustered errors use `G - 1` degrees of freedom for `G` clusters and
+    /// need one label per observation, otherwise this is `LengthMismatch`.
+    pub fn with_covariance(&self, covariance: &Covariance) -> Result<LinearFit, RegressionError> {
+        if let Covariance::Clustered(labels) = covariance {
+            if labels.len() != self.y.len() {
+                return Err(RegressionError::LengthMismatch {
+                    expected: self.y.len(),
+                    found: labels.len(),
+                });
+            }
+        }
+
+        let length = self.y.len();
+        let parameters = self.gram_inverse.len();
+        let mut degrees_of_freedom = length as f64 - parameters as f64;
+
+        let mut meat = vec![vec![0.0; parameters]; parameters];
+        let mut scale = 1.0;
+
+        let hat = || -> Vec<f64> {
+            self.design
+                .iter()
+                .map(|row| linalg::dot(row, &linalg::multiply(&self.gram_inverse, row)))
+                .collect()
+        };
+
+        match covariance {
+            Covariance::Classic => {
+                let mut fit = self.clone();
+                fit.covariance = self
+                    .gram_inverse
+                    .iter()
+                    .map(|row| row.iter().map(|v| v * self.residual_variance).collect())
+                    .collect();
+                fit.covariance_type = Covariance::Classic;
+                return Ok(fit.rebuild(degrees_of_freedom));
+            }
+            Covariance::HC0 | Covariance::HC1 => {
+                for (row, e) in self.design.iter().zip(&self.residuals) {
+                    add_outer(&mut meat, row, row, e * e);
+                }
+                if *covariance == Covariance::HC1 {
+                    scale = length as f64 / degrees_of_freedom;
+                }
+            }
+            Covariance::HC2 | Covariance::HC3 => {
+                let power = if *covariance == Covariance::HC2 { 1 } else { 2 };
+                for ((row, e), h) in self.design.iter().zip(&self.residuals).zip(hat()) {
+                    add_outer(&mut meat, row, row, e * e / (1.0 - h).powi(power));
+                }
+            }
+            Covariance::NeweyWest { lags } => {
+                for (row, e) in self.design.iter().zip(&self.residuals) {
+                    add_outer(&mut meat, row, row, e * e);
+                }
+                for lag in 1..=*lags {
+                    let weight = 1.0 - lag as f64 / (*lags as f64 + 1.0);
+                    for t in lag..length {
+                        let product = weight * self.residuals[t] * self.residuals[t - lag];
+                        add_outer(&mut meat, &self.design[t], &self.design[t - lag], product);
+                        add_outer(&mut meat, &self.design[t - lag], &self.design[t], product);
+                    }
+                }
+            }
+            Covariance::Clustered(labels) => {
+                // Ordered, so the scores are summed the same way on every run
+                let mut scores: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+                for ((row, e), label) in self.design.iter().zip(&self.residuals).zip(labels) {
+                    let score = scores
+                        .entry(label.as_str())
+                        .or_insert_with(|| vec![0.0; parameters]);
+                    for (s, x) in score.iter_mut().zip(row) {
+                        *s += x * e;
+                    }
+                }
+                for score in scores.values() {
+                    add_outer(&mut meat, score, score, 1.0);
+                }
+
+                // Small sample correction used by Stata and statsmodels
+                let groups = scores.len() as f64;
+                scale = groups / (groups - 1.0) * (length as f64 - 1.0)
+                    / (length as f64 - parameters as f64);
+                degrees_of_freedom = groups - 1.0;
+            }
+        }
+
+        // Sandwich (XᵀX)⁻¹ · meat · (XᵀX)⁻¹
+        let bread = &self.gram_inverse;
+        let left: Vec<Vec<f64>> = bread
+            .iter()
+            .map(|row| {
+                (0..parameters)
+                    .map(|j| row.iter().zip(&meat).map(|(b, m)| b * m[j]).sum())
+                    .collect()
+            })
+            .collect();
+
+        let mut fit = self.clone();
+        fit.covariance = left
+            .iter()
+            .map(|row| {
+                (0..parameters)
+                    .map(|j| scale * row.iter().zip(bread).map(|(l, b)| l * b[j]).sum::<f64>())
+                    .collect()
+            })
+            .collect();
+        fit.covariance_type = covariance.clone();
+
+        Ok(fit.rebuild(degrees_of_freedom))
+    }
+
+    fn rebuild(mut self, degrees_of_freedom: f64) -> LinearFit {
+        let parameters: Vec<f64> = std::iter::once(self.intercept.estimate)
+            .chain(self.coefficients.iter().map(|c| c.estimate))
+            .collect();
+
+        (self.intercept, self.coefficients) =
+            coefficient_table(&parameters, &self.covariance, degrees_of_freedom);
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Inference, LinearFrame};
+
+    fn fit() -> LinearFit {
+        LinearFrame {
+            x: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0],
+            y: vec![1.2, 1.9, 3.4, 3.6, 5.9, 5.1, 8.4, 6.8],
+            weights: None,
+            verbose: false,
+        }
+        .inference()
+    }
+
+    #[test]
+    fn hc_test() {
+        let fit = fit();
+        let hc0 = fit.with_covariance(&Covariance::HC0).unwrap();
+
+        // Var(slope) = Σ (x - mean(x))² e² / Sxx² with mean(x) = 4.5, Sxx = 42
+        let expected: f64 = (1..=8)
+            .zip(&fit.residuals)
+            .map(|(x, e)| (x as f64 - 4.5).powi(2) * e * e)
+            .sum::<f64>()
+            / (42.0 * 42.0);
+        assert!(f64::abs(hc0.coefficients[0].standard_error - expected.sqrt()) < 1e-12);
+        assert_eq!(hc0.coefficients[0].estimate, fit.coefficients[0].estimate);
+
+        let hc1 = fit.with_covariance(&Covariance::HC1).unwrap();
+        assert!(f64::abs(hc1.covariance[1][1] - hc0.covariance[1][1] * 8.0 / 6.0) < 1e-12);
+
+        let hc2 = fit.with_covariance(&Covariance::HC2).unwrap();
+        let hc3 = fit.with_covariance(&Covariance::HC3).unwrap();
+        assert!(hc0.covariance[1][1] < hc2.covariance[1][1]);
+        assert!(hc2.covariance[1][1] < hc3.covariance[1][1]);
+
+        let classic = hc3.with_covariance(&Covariance::Classic).unwrap();
+        assert_eq!(classic.covariance_type, Covariance::Classic);
+        assert!(f64::abs(classic.intercept.standard_error - fit.intercept.standard_error) < 1e-12);
+    }
+
+    #[test]
+    fn newey_west_test() {
+        let fit = fit();
+
+        let hc0 = fit.with_covariance(&Covariance::HC0).unwrap();
+        let no_lags = fit
+            .with_covariance(&Covariance::NeweyWest { lags: 0 })
+            .unwrap();
+        assert!(f64::abs(no_lags.covariance[1][1] - hc0.covariance[1][1]) < 1e-15);
+
+        let lagged = fit
+            .with_covariance(&Covariance::NeweyWest { lags: 2 })
+            .unwrap();
+        assert!(f64::abs(lagged.covariance[0][1] - lagged.covariance[1][0]) < 1e-15);
+        assert_ne!(lagged.covariance[1][1], hc0.covariance[1][1]);
+
+        assert_eq!(Covariance::default_lags(100), 4);
+        assert_eq!(Covariance::default_lags(1000), 6);
+    }
+
+    #[test]
+    fn clustered_test() {
+        let fit = fit();
+
+        // One observation per cluster reduces to HC1
+        let singletons: Vec<String> = (0..8).map(|i| i.to_string()).collect();
+        let clustered = fit
+            .with_covariance(&Covariance::Clustered(singletons))
+            .unwrap();
+        let hc1 = fit.with_covariance(&Covariance::HC1).unwrap();
+        assert!(f64::abs(clustered.covariance[1][1] - hc1.covariance[1][1]) < 1e-12);
+        assert_eq!(clustered.degrees_of_freedom, fit.degrees_of_freedom);
+        assert_eq!(clustered.coefficients[0].degrees_of_freedom, 7.0);
+
+        let pairs: Vec<String> = (0..8).map(|i| (i / 2).to_string()).collect();
+        let clustered = fit.with_covariance(&Covariance::Clustered(pairs)).unwrap();
+        assert_eq!(clustered.coefficients[0].degrees_of_freedom, 3.0);
+
+        // Bit for bit the same whenever it is recomputed
+        let mixed: Vec<String> = (0..8).map(|i| ((i * 5) % 7).to_string()).collect();
+        let first = fit.with_covariance(&Covariance::Clustered(mixed.clone()));
+        for _ in 0..10 {
+            assert_eq!(
+                fit.with_covariance(&Covariance::Clustered(mixed.clone())),
+                first
+            );
+        }
+
+        let short = Covariance::Clustered(vec!["a".to_string(); 7]);
+        assert_eq!(
+            fit.with_covariance(&short).err(),
+            Some(RegressionError::LengthMismatch {
+                expected: 8,
+                found: 7
+            })
+        );
+    }
+}
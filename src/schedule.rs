@@ -0,0 +1,145 @@
+use std::f64::consts::PI;
+use std::iter::zip;
+
+use crate::linalg;
+
+/// Learning rate as a function of the epoch, scaling the trainer's base rate.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Schedule {
+    #[default]
+    Constant,
+    /// Multiplies the rate by `factor` every `every` epochs.
+    Step { every: i32, factor: f64 },
+    /// `rate · decay^epoch`
+    Exponential { decay: f64 },
+    /// Anneals from the base rate down to `minimum` over `epochs` along half a
+    /// cosine, then stays at `minimum`.
+    Cosine { epochs: i32, minimum: f64 },
+    /// `rate / (1 + decay · epoch)`
+    InverseTime { decay: f64 },
+    /// Ramps the rate up linearly over `epochs`, then continues with `then`
+    /// starting from its epoch zero.
+    Warmup { epochs: i32, then: Box<Schedule> },
+}
+
+impl Schedule {
+    pub fn rate(&self, base: f64, epoch: i32) -> f64 {
+        match self {
+            Schedule::Constant => base,
+            Schedule::Step { every, factor } => base * factor.powi(epoch / every.max(&1)),
+            Schedule::Exponential { decay } => base * decay.powi(epoch),
+            Schedule::Cosine { epochs, minimum } => {
+                let progress = f64::min(epoch as f64 / (*epochs).max(1) as f64, 1.0);
+                minimum + (base - minimum) * (1.0 + (PI * progress).cos()) / 2.0
+            }
+            Schedule::InverseTime { decay } => base / (1.0 + decay * epoch as f64),
+            Schedule::Warmup { epochs, then } => {
+                if epoch < *epochs {
+                    base * (epoch + 1) as f64 / *epochs as f64
+                } else {
+                    then.rate(base, epoch - epochs)
+                }
+            }
+        }
+    }
+}
+
+/// Backtracking line search with the Armijo sufficient decrease condition.
+/// Steps proposed by the optimizer are shrunk until the loss drops by at
+/// least `sufficient_decrease` times the decrease predicted by the gradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineSearch {
+    /// Factor applied to the step after every rejected trial.
+    pub shrink: f64,
+    pub sufficient_decrease: f64,
+    pub max_steps: i32,
+}
+
+impl Default for LineSearch {
+    fn default() -> Self {
+        LineSearch {
+            shrink: 0.5,
+            sufficient_decrease: 1e-4,
+            max_steps: 50,
+        }
+    }
+}
+
+impl LineSearch {
+    /// Searches along the step from `start` to `proposed`. `gradient` is the
+    /// gradient of `loss` at `start`. Returns `start` when no trial step
+    /// decreases the loss enough.
+    pub fn backtrack(
+        &self,
+        start: &[f64],
+        proposed: &[f64],
+        gradient: &[f64],
+        loss: impl Fn(&[f64]) -> f64,
+    ) -> Vec<f64> {
+        let direction: Vec<f64> = zip(proposed, start).map(|(p, s)| p - s).collect();
+        let slope = linalg::dot(gradient, &direction);
+        let current = loss(start);
+
+        let mut step = 1.0;
+        for _ in 0..self.max_steps {
+            let trial: Vec<f64> = zip(start, &direction).map(|(s, d)| s + step * d).collect();
+            if loss(&trial) <= current + self.sufficient_decrease * step * slope {
+                return trial;
+            }
+            step *= self.shrink;
+        }
+
+        start.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_test() {
+        let step = Schedule::Step {
+            every: 10,
+            factor: 0.5,
+        };
+        assert_eq!(step.rate(1.0, 9), 1.0);
+        assert_eq!(step.rate(1.0, 25), 0.25);
+
+        assert_eq!(
+            Schedule::Exponential { decay: 0.9 }.rate(2.0, 2),
+            2.0 * 0.81
+        );
+        assert_eq!(Schedule::InverseTime { decay: 0.5 }.rate(1.0, 4), 1.0 / 3.0);
+
+        let cosine = Schedule::Cosine {
+            epochs: 100,
+            minimum: 0.1,
+        };
+        assert_eq!(cosine.rate(1.0, 0), 1.0);
+        assert!(f64::abs(cosine.rate(1.0, 50) - 0.55) < 1e-12);
+        assert!(f64::abs(cosine.rate(1.0, 500) - 0.1) < 1e-12);
+
+        let warmup = Schedule::Warmup {
+            epochs: 4,
+            then: Box::new(Schedule::Exponential { decay: 0.5 }),
+        };
+        assert_eq!(warmup.rate(1.0, 0), 0.25);
+        assert_eq!(warmup.rate(1.0, 3), 1.0);
+        assert_eq!(warmup.rate(1.0, 5), 0.5);
+    }
+
+    #[test]
+    fn backtrack_test() {
+        let loss = |p: &[f64]| p[0] * p[0];
+        let search = LineSearch::default();
+
+        // The proposed step overshoots to -19, halving it gives -9, -4, -1.5
+        // and finally -0.25, the first point with a sufficient decrease
+        let step = search.backtrack(&[1.0], &[-19.0], &[2.0], loss);
+        assert_eq!(step, vec![-0.25]);
+
+        // Ascent directions are never taken
+        assert_eq!(search.backtrack(&[1.0], &[2.0], &[2.0], loss), vec![1.0]);
+    }
+}
@@ -0,0 +1,382 @@
+use std::fmt;
+
+use crate::{GoodnessOfFit, LinearFit};
+
+/// Coefficient table and fit statistics of a `LinearFit`, rendered as plain
+/// text through `Display` or exported with `to_markdown` and `to_latex`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub names: Vec<String>,
+    pub fit: LinearFit,
+    pub goodness: GoodnessOfFit,
+    /// Level of the reported confidence intervals.
+    pub level: f64,
+}
+
+impl Summary {
+    pub fn new(fit: &LinearFit) -> Self {
+        let names = if fit.coefficients.len() == 1 {
+            vec!["x".to_string()]
+        } else {
+            (1..=fit.coefficients.len())
+                .map(|i| format!("x{}", i))
+                .collect()
+        };
+
+        Summary {
+            names: std::iter::once("const".to_string()).chain(names).collect(),
+            fit: fit.clone(),
+            goodness: fit.goodness_of_fit(),
+            level: 0.95,
+        }
+    }
+
+    /// Names of the features, the intercept is always called `const`.
+    /// Coefficients without a name keep their default `x<i>`, names beyond
+    /// the last coefficient are ignored.
+    pub fn with_names(mut self, names: &[&str]) -> Self {
+        for (slot, name) in self.names[1..].iter_mut().zip(names) {
+            *slot = name.to_string();
+        }
+        self
+    }
+
+    pub fn with_level(mut self, level: f64) -> Self {
+        self.level = level;
+        self
+    }
+
+    fn header(&self) -> [String; 7] {
+        let tail = (1.0 - self.level) / 2.0;
+
+        [
+            String::new(),
+            "estimate".to_string(),
+            "std err".to_string(),
+            "t".to_string(),
+            "P>|t|".to_string(),
+            format!("[{}", format_number(tail)),
+            format!("{}]", format_number(1.0 - tail)),
+        ]
+    }
+
+    fn rows(&self) -> Vec<[String; 7]> {
+        std::iter::once(&self.fit.intercept)
+            .chain(&self.fit.coefficients)
+            .zip(&self.names)
+            .map(|(c, name)| {
+                let (low, high) = c.confidence_interval(self.level);
+                [
+                    name.clone(),
+                    format_value(c.estimate, 4),
+                    format_value(c.standard_error, 4),
+                    format_value(c.t_statistic, 3),
+                    format_value(c.p_value, 3),
+                    format_value(low, 4),
+                    format_value(high, 4),
+                ]
+            })
+            .collect()
+    }
+
+    fn statistics(&self) -> Vec<(&'static str, String)> {
+        let goodness = &self.goodness;
+
+        vec![
+            ("Observations", self.fit.y.len().to_string()),
+            ("Df Residuals", self.fit.degrees_of_freedom.to_string()),
+            ("R-squared", format_value(goodness.r_squared, 4)),
+            (
+                "Adj. R-squared",
+                format_value(goodness.adjusted_r_squared, 4),
+            ),
+            ("F-statistic", format_value(goodness.f_statistic, 4)),
+            ("Prob (F-statistic)", format!("{:.4e}", goodness.f_p_value)),
+            (
+                "Residual variance",
+                format_value(self.fit.residual_variance, 4),
+            ),
+            ("Log-Likelihood", format_value(goodness.log_likelihood, 4)),
+            ("AIC", format_value(goodness.aic, 4)),
+            ("BIC", format_value(goodness.bic, 4)),
+            ("Covariance Type", self.fit.covariance_type.to_string()),
+        ]
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let cells = |row: &[String]| -> String {
+            let escaped: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            format!("| {} |\n", escaped.join(" | "))
+        };
+
+        out.push_str(&cells(&self.header()));
+        out.push_str(&format!("|{}\n", " --- |".repeat(7)));
+        for row in self.rows() {
+            out.push_str(&cells(&row));
+        }
+
+        out.push_str("\n| Statistic | Value |\n| --- | --- |\n");
+        for (label, value) in self.statistics() {
+            out.push_str(&format!("| {} | {} |\n", label, value));
+        }
+
+        out
+    }
+
+    pub fn to_latex(&self) -> String {
+        let mut out = String::new();
+        let cells = |row: &[String]| -> String { format!("{} \\\\\n", row.join(" & ")) };
+
+        let mut header = self.header();
+        header[4] = "P$>|t|$".to_string();
+
+        out.push_str("\\begin{tabular}{lrrrrrr}\n\\hline\n");
+        out.push_str(&cells(&header));
+        out.push_str("\\hline\n");
+        for mut row in self.rows() {
+            row[0] = escape_latex(&row[0]);
+            out.push_str(&cells(&row));
+        }
+        out.push_str("\\hline\n\\end{tabular}\n\n");
+
+        out.push_str("\\begin{tabular}{lr}\n\\hline\n");
+        for (label, value) in self.statistics() {
+            out.push_str(&format!("{} & {} \\\\\n", label, value));
+        }
+        out.push_str("\\hline\n\\end{tabular}\n");
+
+        out
+    }
+}
+
+/// Fixed notation with `decimals` digits, switching to scientific notation
+/// for magnitudes that would be too long or round to zero.
+fn format_value(value: f64, decimals: usize) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 1e6 || (magnitude > 0.0 && magnitude < 1e-3) {
+        format!("{:.*e}", decimals, value)
+    } else {
+        format!("{:.*}", decimals, value)
+    }
+}
+
+fn format_number(value: f64) -> String {
+    let text = format!("{:.3}", value);
+    text.trim_end_matches('0').to_string()
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        if "&%$#_{}".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Separates the columns of the plain text table.
+const GAP: &str = "  ";
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = self.header();
+        let rows = self.rows();
+        let name_width = self
+            .names
+            .iter()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+        // Every column is as wide as its longest cell, and at least 9
+        let widths: Vec<usize> = (1..7)
+            .map(|j| {
+                rows.iter()
+                    .chain(std::iter::once(&header))
+                    .map(|row| row[j].len())
+                    .fold(9, usize::max)
+            })
+            .collect();
+        let width = name_width + widths.iter().map(|w| w + GAP.len()).sum::<usize>();
+        let line = |f: &mut fmt::Formatter<'_>, c: &str| writeln!(f, "{}", c.repeat(width));
+
+        line(f, "=")?;
+        let write_row = |f: &mut fmt::Formatter<'_>, row: &[String]| -> fmt::Result {
+            write!(f, "{:<name_width$}", row[0])?;
+            for (cell, width) in row[1..].iter().zip(&widths) {
+                write!(f, "{}{:>width$}", GAP, cell)?;
+            }
+            writeln!(f)
+        };
+
+        write_row(f, &header)?;
+        line(f, "-")?;
+        for row in rows {
+            write_row(f, &row)?;
+        }
+        line(f, "-")?;
+
+        let statistics = self.statistics();
+        let half = width / 2;
+        for pair in statistics.chunks(2) {
+            let mut cells = pair
+                .iter()
+                .map(|(label, value)| format!("{:<20}{:>w$}", label, value, w = half - 22));
+            write!(f, "{}", cells.next().unwrap_or_default())?;
+            if let Some(cell) = cells.next() {
+                write!(f, "  {}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        line(f, "=")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame;
+    use crate::{Inference, LinearFrame, MultiFrame, MultiRegression, Regression};
+
+    fn summary() -> Summary {
+        let mut frame = frame();
+
+        frame.inference().summary()
+    }
+
+    #[test]
+    fn display_test() {
+        let text = summary().to_string();
+
+        assert!(text.contains("const"));
+        assert!(text.contains("P>|t|"));
+        assert!(text.contains("[0.025"));
+        assert!(text.contains("0.975]"));
+        assert!(text.contains("1.0000"));
+        assert!(text.contains("R-squared"));
+        assert!(text.contains("0.9259"));
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[1].ends_with("0.975]"));
+        assert!(lines[3].starts_with("const"));
+        assert!(lines[4].starts_with("x "));
+    }
+
+    #[test]
+    fn wide_values_test() {
+        let mut frame = frame();
+        frame.y = frame.y.iter().map(|y| y * 1e9).collect();
+
+        let text = frame.inference().summary().to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        // Large values switch to scientific notation and stay separate cells
+        assert!(lines[3].contains("2.0000e8"));
+        for line in &lines[3..5] {
+            assert_eq!(line.split_whitespace().count(), 7);
+        }
+        assert!(text.contains("F-statistic"));
+        assert!(lines.iter().all(|line| line.len() <= lines[0].len()));
+    }
+
+    #[test]
+    fn markdown_test() {
+        let markdown = summary()
+            .with_names(&["dose"])
+            .with_level(0.9)
+            .to_markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "|  | estimate | std err | t | P>\\|t\\| | [0.05 | 0.95] |"
+        );
+        assert_eq!(lines[1], "| --- | --- | --- | --- | --- | --- | --- |");
+        assert!(lines[3].starts_with("| dose | 1.0000 | 0.1633 | 6.124 |"));
+        assert!(markdown.contains("| R-squared | 0.9259 |"));
+        assert!(markdown.contains("| Covariance Type | nonrobust |"));
+    }
+
+    #[test]
+    fn latex_test() {
+        let mut frame = MultiFrame {
+            x: vec![
+                vec![1.0, 2.0],
+                vec![2.0, 1.0],
+                vec![3.0, 4.0],
+                vec![4.0, 3.0],
+                vec![5.0, 5.0],
+                vec![0.0, 1.0],
+            ],
+            y: vec![3.1, 6.0, 5.2, 7.9, 8.0, 2.1],
+            weights: None,
+            verbose: false,
+        };
+
+        let latex = frame
+            .inference()
+            .summary()
+            .with_names(&["dose_mg", "age"])
+            .to_latex();
+
+        assert!(latex.starts_with("\\begin{tabular}{lrrrrrr}"));
+        assert!(latex.contains("dose\\_mg & "));
+        assert!(latex.contains("age & "));
+        assert!(latex.contains("P$>|t|$"));
+        assert!(latex.contains("AIC & "));
+
+        // Unnamed coefficients keep their default names
+        let text = frame
+            .inference()
+            .summary()
+            .with_names(&["dose"])
+            .to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[4].starts_with("dose "));
+        assert!(lines[5].starts_with("x2 "));
+    }
+
+    #[test]
+    fn underdetermined_test() {
+        // Verbose fits print a summary, which must not fail without residual
+        // degrees of freedom
+        let mut frame = LinearFrame {
+            x: vec![1.0],
+            y: vec![2.0],
+            weights: None,
+            verbose: true,
+        };
+        frame.regression(3, 0.01);
+        frame.least_squares();
+
+        let mut frame = MultiFrame {
+            x: vec![vec![1.0, 2.0], vec![2.0, 1.0]],
+            y: vec![1.0, 2.0],
+            weights: None,
+            verbose: true,
+        };
+        let (coefficients, b) = frame.regression(3, 0.01);
+        frame.least_squares();
+
+        let text = frame.summary(&coefficients, b).to_string();
+        assert!(text.contains("Df Residuals"));
+        assert!(text.contains("NaN"));
+
+        // Nor without any samples
+        let mut frame = LinearFrame {
+            x: vec![],
+            y: vec![],
+            weights: None,
+            verbose: true,
+        };
+        frame.regression(3, 0.01);
+        frame.least_squares();
+
+        let mut frame = MultiFrame::from(&frame);
+        frame.regression(3, 0.01);
+        frame.least_squares();
+    }
+}
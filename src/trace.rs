@@ -0,0 +1,116 @@
+/// State after one epoch or iteration, a debug event with the given fields
+/// under the `tracing` feature. It is never printed: per-epoch output is the
+/// job of the callbacks of `Train::train`.
+macro_rules! epoch {
+    ($name:ident = $epoch:expr $(, $($fields:tt)*)?) => {{
+        #[cfg(feature = "tracing")]
+        tracing::debug!($name = $epoch $(, $($fields)*)?);
+        #[cfg(not(feature = "tracing"))]
+        let _ = $epoch;
+    }};
+}
+
+/// Result of a fit without a summary table. With the `tracing` feature it is
+/// a debug event with the given fields and nothing is ever printed; otherwise
+/// the message is printed to stdout when `verbose` is set.
+macro_rules! progress {
+    ($verbose:expr, [$($fields:tt)*], $($message:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        {
+            let _ = $verbose;
+            tracing::debug!($($fields)*);
+        }
+        #[cfg(not(feature = "tracing"))]
+        if $verbose {
+            println!($($message)+);
+        }
+    }};
+}
+
+/// Summary table of a finished fit. It is only built when it will be shown.
+macro_rules! finished {
+    ($verbose:expr, $summary:expr) => {{
+        #[cfg(feature = "tracing")]
+        {
+            let _ = $verbose;
+            tracing::debug!(summary = %$summary, "fit finished");
+        }
+        #[cfg(not(feature = "tracing"))]
+        if $verbose {
+            println!("{}", $summary);
+        }
+    }};
+}
+
+/// Enters an info level span named after the solver until the end of the
+/// enclosing block.
+macro_rules! fit_span {
+    ($($fields:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!($($fields)*).entered();
+    };
+}
+
+pub(crate) use {epoch, finished, fit_span, progress};
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::{LinearFrame, Regression};
+
+    #[derive(Default)]
+    struct Counter {
+        spans: AtomicUsize,
+        events: AtomicUsize,
+        next: AtomicU64,
+    }
+
+    struct Counting(Arc<Counter>);
+
+    impl Subscriber for Counting {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            self.0.spans.fetch_add(1, Ordering::SeqCst);
+            Id::from_u64(self.0.next.fetch_add(1, Ordering::SeqCst) + 1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {
+            self.0.events.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn tracing_test() {
+        let counter = Arc::new(Counter::default());
+        let mut frame = LinearFrame {
+            x: vec![1.0, 2.0, 3.0],
+            y: vec![2.0, 4.0, 6.0],
+            weights: None,
+            verbose: false,
+        };
+
+        tracing::subscriber::with_default(Counting(counter.clone()), || {
+            frame.regression(10, 0.01);
+        });
+
+        // One span for the fit, an event per epoch and the summary
+        assert_eq!(counter.spans.load(Ordering::SeqCst), 1);
+        assert_eq!(counter.events.load(Ordering::SeqCst), 11);
+    }
+}
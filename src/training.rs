@@ -0,0 +1,585 @@
+use std::fmt;
+use std::iter::zip;
+
+use crate::callback::{Callback, Control, EpochRecord, History, Logger};
+use crate::linalg;
+use crate::multi::{batch_gradient, predict, weight};
+use crate::optimizer::{Optimizer, Sgd};
+use crate::penalty::Penalty;
+use crate::rng::Rng;
+use crate::schedule::{LineSearch, Schedule};
+use crate::trace::{finished, fit_span};
+use crate::{LinearFrame, MultiFrame, MultiRegression, RegressionError};
+
+/// When gradient descent stops. Each tolerance is checked after every epoch
+/// and training stops at the first one the change falls below; a tolerance of
+/// zero disables its check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stopping {
+    pub max_epochs: i32,
+    /// Largest absolute change of the loss between two epochs.
+    pub loss_tolerance: f64,
+    /// Largest change of the loss relative to its previous value.
+    pub relative_tolerance: f64,
+    /// Largest Euclidean norm of the gradient, intercept included. For
+    /// stochastic and mini-batch fits this is the mean of the epoch's batch
+    /// gradients.
+    pub gradient_tolerance: f64,
+    /// Largest absolute change of any parameter between two epochs.
+    pub parameter_tolerance: f64,
+    /// Number of consecutive epochs with a rising loss above its starting
+    /// value that count as divergence.
+    pub patience: i32,
+}
+
+impl Default for Stopping {
+    fn default() -> Self {
+        Stopping {
+            max_epochs: 1000,
+            loss_tolerance: 0.0,
+            relative_tolerance: 0.0,
+            gradient_tolerance: 1e-8,
+            parameter_tolerance: 0.0,
+            patience: 10,
+        }
+    }
+}
+
+/// Why training stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    MaxEpochs,
+    LossChange,
+    RelativeLossChange,
+    GradientNorm,
+    ParameterChange,
+    /// The loss became non-finite or kept rising above its starting value
+    /// for `Stopping::patience` epochs.
+    Diverged,
+    /// A callback asked to stop.
+    Callback,
+}
+
+/// How many samples each gradient step looks at. An epoch is always one
+/// pass over the whole frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Batch {
+    /// One step per epoch on the exact gradient.
+    #[default]
+    Full,
+    /// One step per sample.
+    Stochastic,
+    /// One step per batch of the given size; the last batch of an epoch may
+    /// be smaller.
+    MiniBatch(usize),
+}
+
+impl Batch {
+    fn size(&self, samples: usize) -> usize {
+        match *self {
+            Batch::Full => samples,
+            Batch::Stochastic => 1,
+            Batch::MiniBatch(size) => size.clamp(1, samples.max(1)),
+        }
+    }
+}
+
+/// Settings for `Train::train`.
+pub struct Trainer {
+    pub learning_rate: f64,
+    pub stopping: Stopping,
+    /// Update rule, plain gradient descent by default. It is reset at the
+    /// start of every fit.
+    pub optimizer: Box<dyn Optimizer>,
+    pub batch: Batch,
+    /// Whether samples are shuffled before every epoch of a stochastic or
+    /// mini-batch fit.
+    pub shuffle: bool,
+    /// Seed of the shuffling, so runs are reproducible.
+    pub seed: u64,
+    /// Scales `learning_rate` from epoch to epoch.
+    pub schedule: Schedule,
+    /// Shrinks steps that do not decrease the loss of their batch enough,
+    /// so a too large learning rate cannot diverge.
+    pub line_search: Option<LineSearch>,
+    /// Invoked in order after every epoch.
+    pub callbacks: Vec<Box<dyn Callback>>,
+}
+
+impl fmt::Debug for Trainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trainer")
+            .field("learning_rate", &self.learning_rate)
+            .field("stopping", &self.stopping)
+            .field("optimizer", &self.optimizer)
+            .field("batch", &self.batch)
+            .field("shuffle", &self.shuffle)
+            .field("seed", &self.seed)
+            .field("schedule", &self.schedule)
+            .field("line_search", &self.line_search)
+            .field("callbacks", &self.callbacks.len())
+            .finish()
+    }
+}
+
+impl Trainer {
+    pub fn new(learning_rate: f64) -> Self {
+        Trainer {
+            learning_rate,
+            stopping: Stopping::default(),
+            optimizer: Box::new(Sgd),
+            batch: Batch::Full,
+            shuffle: true,
+            seed: 0,
+            schedule: Schedule::Constant,
+            line_search: None,
+            callbacks: Vec::new(),
+        }
+    }
+
+    pub fn with_callback(mut self, callback: impl Callback + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    pub fn with_line_search(mut self, line_search: LineSearch) -> Self {
+        self.line_search = Some(line_search);
+        self
+    }
+
+    pub fn with_batch(mut self, batch: Batch) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    pub fn with_shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_optimizer(mut self, optimizer: impl Optimizer + 'static) -> Self {
+        self.optimizer = Box::new(optimizer);
+        self
+    }
+
+    pub fn with_stopping(mut self, stopping: Stopping) -> Self {
+        self.stopping = stopping;
+        self
+    }
+}
+
+/// Parameters found by `Train::train` and how training ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingResult {
+    pub coefficients: Vec<f64>,
+    pub b: f64,
+    /// Mean squared error at the returned parameters.
+    pub loss: f64,
+    /// Number of completed passes over the frame.
+    pub epochs: i32,
+    pub reason: StopReason,
+    pub history: History,
+}
+
+impl TrainingResult {
+    /// True unless training diverged or ran out of epochs.
+    pub fn converged(&self) -> bool {
+        !matches!(self.reason, StopReason::MaxEpochs | StopReason::Diverged)
+    }
+}
+
+/// Mean squared error over the samples in `batch` of the parameters ordered
+/// intercept first.
+fn batch_loss(frame: &MultiFrame, batch: &[usize], parameters: &[f64]) -> f64 {
+    let mut error = 0.0;
+    let mut length = 0.0;
+
+    for &i in batch {
+        let w = weight(&frame.weights, i);
+        let delta = frame.y[i] - predict(&parameters[1..], parameters[0], &frame.x[i]);
+        error += w * delta * delta;
+        length += w;
+    }
+
+    error / f64::max(length, f64::MIN_POSITIVE)
+}
+
+/// Gradient descent that stops on convergence or divergence instead of
+/// running a fixed number of epochs.
+pub trait Train {
+    fn train(&mut self, trainer: &mut Trainer) -> Result<TrainingResult, RegressionError>;
+}
+
+impl Train for MultiFrame {
+    fn train(&mut self, trainer: &mut Trainer) -> Result<TrainingResult, RegressionError> {
+        self.validate()?;
+
+        fit_span!(
+            "train",
+            samples = self.x.len(),
+            features = self.features(),
+            learning_rate = trainer.learning_rate,
+            optimizer = ?trainer.optimizer
+        );
+
+        let stopping = trainer.stopping;
+        trainer.optimizer.reset();
+
+        // Intercept first, then the coefficients
+        let mut parameters = vec![0.0; self.features() + 1];
+        let mut loss = self.mean_squared_error(&|x| predict(&parameters[1..], parameters[0], x));
+        let initial = loss;
+
+        let mut reason = StopReason::MaxEpochs;
+        let mut epochs = 0;
+        let mut rising = 0;
+        let mut history = History::default();
+        // Epochs are debug events instead with the `tracing` feature
+        let mut logger = (self.verbose && !cfg!(feature = "tracing")).then(Logger::stdout);
+
+        let size = trainer.batch.size(self.x.len());
+        let mut order: Vec<usize> = (0..self.x.len()).collect();
+        let mut rng = Rng::new(trainer.seed);
+
+        while epochs < stopping.max_epochs {
+            if trainer.shuffle && size < order.len() {
+                rng.shuffle(&mut order);
+            }
+
+            let rate = trainer.schedule.rate(trainer.learning_rate, epochs);
+            let mut next = parameters.clone();
+            let mut mean_gradient = vec![0.0; next.len()];
+            let batches = order.len().div_ceil(size) as f64;
+
+            for batch in order.chunks(size) {
+                let (coefficient_gradients, b_gradient) = batch_gradient(
+                    &self.x,
+                    &self.y,
+                    &self.weights,
+                    batch.iter().copied(),
+                    &next[1..],
+                    next[0],
+                    &Penalty::None,
+                );
+                let gradients: Vec<f64> = std::iter::once(b_gradient)
+                    .chain(coefficient_gradients)
+                    .collect();
+
+                for (mean, g) in zip(&mut mean_gradient, &gradients) {
+                    *mean += g / batches;
+                }
+
+                let start = next.clone();
+                trainer.optimizer.step(&mut next, &gradients, rate);
+                if let Some(search) = &trainer.line_search {
+                    next = search.backtrack(&start, &next, &gradients, |parameters| {
+                        batch_loss(self, batch, parameters)
+                    });
+                }
+            }
+
+            let next_loss = self.mean_squared_error(&|x| predict(&next[1..], next[0], x));
+
+            // Keep the last finite parameters when the loss blows up
+            if !next_loss.is_finite() {
+                reason = StopReason::Diverged;
+                break;
+            }
+
+            let parameter_change = zip(&parameters, &next)
+                .map(|(p, n)| (n - p).abs())
+                .fold(0.0, f64::max);
+            let change = next_loss - loss;
+
+            parameters = next;
+            let previous = loss;
+            loss = next_loss;
+            epochs += 1;
+
+            let gradient_norm = linalg::dot(&mean_gradient, &mean_gradient).sqrt();
+            let record = EpochRecord {
+                epoch: epochs - 1,
+                coefficients: parameters[1..].to_vec(),
+                b: parameters[0],
+                loss,
+                gradient_norm,
+                learning_rate: rate,
+            };
+
+            // Every callback sees the epoch, even after one asked to stop
+            let mut stop = false;
+            for callback in logger
+                .iter_mut()
+                .map(|logger| logger as &mut dyn Callback)
+                .chain(
+                    trainer
+                        .callbacks
+                        .iter_mut()
+                        .map(|callback| callback.as_mut()),
+                )
+            {
+                stop |= callback.on_epoch(&record) == Control::Stop;
+            }
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                epoch = record.epoch,
+                loss,
+                gradient_norm,
+                learning_rate = rate
+            );
+            history.records.push(record);
+
+            if stop {
+                reason = StopReason::Callback;
+                break;
+            }
+
+            // Adaptive optimizers oscillate near the minimum, which is not
+            // divergence
+            rising = if change > 0.0 && loss > initial {
+                rising + 1
+            } else {
+                0
+            };
+            if rising >= stopping.patience {
+                reason = StopReason::Diverged;
+                break;
+            }
+            if gradient_norm < stopping.gradient_tolerance {
+                reason = StopReason::GradientNorm;
+                break;
+            }
+            if change.abs() < stopping.loss_tolerance {
+                reason = StopReason::LossChange;
+                break;
+            }
+            if change.abs() < stopping.relative_tolerance * previous.abs() {
+                reason = StopReason::RelativeLossChange;
+                break;
+            }
+            if parameter_change < stopping.parameter_tolerance {
+                reason = StopReason::ParameterChange;
+                break;
+            }
+        }
+
+        let b = parameters.remove(0);
+        let coefficients = parameters;
+
+        finished!(self.verbose, self.summary(&coefficients, b));
+
+        Ok(TrainingResult {
+            coefficients,
+            b,
+            loss,
+            epochs,
+            reason,
+            history,
+        })
+    }
+}
+
+impl Train for LinearFrame {
+    fn train(&mut self, trainer: &mut Trainer) -> Result<TrainingResult, RegressionError> {
+        self.validate()?;
+        MultiFrame::from(&*self).train(trainer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame;
+
+    #[test]
+    fn converges_test() {
+        let mut trainer = Trainer::new(0.05).with_stopping(Stopping {
+            max_epochs: 100_000,
+            ..Stopping::default()
+        });
+
+        let result = frame().train(&mut trainer).unwrap();
+
+        assert_eq!(result.reason, StopReason::GradientNorm);
+        assert!(result.converged());
+        assert!(result.epochs < 100_000);
+        assert!(f64::abs(result.coefficients[0] - 1.0) < 1e-6);
+        assert!(f64::abs(result.b - 0.2) < 1e-6);
+        assert!(f64::abs(result.loss - 0.16) < 1e-10);
+    }
+
+    #[test]
+    fn stopping_test() {
+        let mut trainer = Trainer::new(0.01).with_stopping(Stopping {
+            max_epochs: 50,
+            ..Stopping::default()
+        });
+        let result = frame().train(&mut trainer).unwrap();
+        assert_eq!(result.reason, StopReason::MaxEpochs);
+        assert_eq!(result.epochs, 50);
+        assert!(!result.converged());
+
+        let mut trainer = Trainer::new(0.01).with_stopping(Stopping {
+            max_epochs: 100_000,
+            loss_tolerance: 1e-6,
+            ..Stopping::default()
+        });
+        let result = frame().train(&mut trainer).unwrap();
+        assert_eq!(result.reason, StopReason::LossChange);
+
+        let mut trainer = Trainer::new(0.01).with_stopping(Stopping {
+            max_epochs: 100_000,
+            parameter_tolerance: 1e-4,
+            ..Stopping::default()
+        });
+        let result = frame().train(&mut trainer).unwrap();
+        assert_eq!(result.reason, StopReason::ParameterChange);
+    }
+
+    #[test]
+    fn diverges_test() {
+        let result = frame().train(&mut Trainer::new(1.0)).unwrap();
+
+        assert_eq!(result.reason, StopReason::Diverged);
+        assert_eq!(result.epochs, Stopping::default().patience);
+        assert!(result.loss.is_finite());
+
+        // With patience out of reach the loss overflows instead
+        let mut trainer = Trainer::new(1.0).with_stopping(Stopping {
+            patience: i32::MAX,
+            ..Stopping::default()
+        });
+        let result = frame().train(&mut trainer).unwrap();
+        assert_eq!(result.reason, StopReason::Diverged);
+        assert!(result.b.is_finite());
+    }
+
+    #[test]
+    fn optimizer_test() {
+        use crate::optimizer::Adam;
+
+        // The second feature is a thousand times larger than the first
+        let x: Vec<Vec<f64>> = (0..20)
+            .map(|i| vec![(i % 5) as f64 / 5.0, 1000.0 * (i / 5) as f64])
+            .collect();
+        let y = x.iter().map(|x| 3.0 * x[0] + 0.002 * x[1] + 1.0).collect();
+        let mut frame = MultiFrame::new(x, y).unwrap();
+        let stopping = Stopping {
+            max_epochs: 5000,
+            ..Stopping::default()
+        };
+
+        let mut sgd = Trainer::new(1e-7).with_stopping(stopping);
+        let sgd = frame.train(&mut sgd).unwrap();
+        let mut adam = Trainer::new(0.01)
+            .with_stopping(stopping)
+            .with_optimizer(Adam::default());
+        let adam = frame.train(&mut adam).unwrap();
+
+        assert!(adam.loss < sgd.loss / 100.0);
+        assert!(f64::abs(adam.coefficients[0] - 3.0) < 1e-2);
+    }
+
+    #[test]
+    fn mini_batch_test() {
+        let x: Vec<Vec<f64>> = (0..200).map(|i| vec![(i % 20) as f64 / 10.0]).collect();
+        let y = x.iter().map(|x| 2.0 * x[0] - 1.0).collect();
+        let mut frame = MultiFrame::new(x, y).unwrap();
+        let stopping = Stopping {
+            max_epochs: 200,
+            ..Stopping::default()
+        };
+
+        let mut fit = |batch: Batch, shuffle: bool, seed: u64| {
+            let mut trainer = Trainer::new(0.05)
+                .with_stopping(stopping)
+                .with_batch(batch)
+                .with_shuffle(shuffle)
+                .with_seed(seed);
+            frame.train(&mut trainer).unwrap()
+        };
+
+        let stochastic = fit(Batch::Stochastic, true, 1);
+        assert!(f64::abs(stochastic.coefficients[0] - 2.0) < 1e-6);
+        assert!(f64::abs(stochastic.b + 1.0) < 1e-6);
+
+        // Runs are reproducible for a seed and differ between seeds
+        let mini_batch = fit(Batch::MiniBatch(16), true, 1);
+        assert_eq!(fit(Batch::MiniBatch(16), true, 1), mini_batch);
+        assert_ne!(fit(Batch::MiniBatch(16), true, 2), mini_batch);
+        assert!(mini_batch.loss < 1e-8);
+
+        // A single unshuffled batch is plain gradient descent
+        assert_eq!(
+            fit(Batch::MiniBatch(1000), false, 1),
+            fit(Batch::Full, true, 1)
+        );
+    }
+
+    #[test]
+    fn step_size_test() {
+        let stopping = Stopping {
+            max_epochs: 10_000,
+            gradient_tolerance: 1e-6,
+            ..Stopping::default()
+        };
+
+        // A rate of 1 diverges on its own, the line search shrinks it
+        let mut trainer = Trainer::new(1.0)
+            .with_stopping(stopping)
+            .with_line_search(LineSearch::default());
+        let result = frame().train(&mut trainer).unwrap();
+        assert_eq!(result.reason, StopReason::GradientNorm);
+        assert!(f64::abs(result.coefficients[0] - 1.0) < 1e-5);
+
+        // Warm up to a stable rate, then anneal it
+        let mut trainer =
+            Trainer::new(0.05)
+                .with_stopping(stopping)
+                .with_schedule(Schedule::Warmup {
+                    epochs: 10,
+                    then: Box::new(Schedule::Cosine {
+                        epochs: 1000,
+                        minimum: 0.01,
+                    }),
+                });
+        let result = frame().train(&mut trainer).unwrap();
+        assert!(result.converged());
+        assert!(f64::abs(result.b - 0.2) < 1e-5);
+    }
+
+    #[test]
+    fn callback_test() {
+        let mut trainer = Trainer::new(0.01).with_callback(|record: &EpochRecord| {
+            if record.epoch == 4 {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        });
+
+        let result = frame().train(&mut trainer).unwrap();
+
+        assert_eq!(result.reason, StopReason::Callback);
+        assert_eq!(result.epochs, 5);
+        assert_eq!(result.history.records.len(), 5);
+
+        let last = result.history.records.last().unwrap();
+        assert_eq!(last.loss, result.loss);
+        assert_eq!(last.coefficients, result.coefficients);
+        assert_eq!(last.learning_rate, 0.01);
+
+        let losses = result.history.losses();
+        assert!(losses.windows(2).all(|pair| pair[1] < pair[0]));
+    }
+}